@@ -0,0 +1,149 @@
+/*
+ * Boards
+ * ------
+ * Several secret words played at once with the same guesses. A board is
+ * frozen when its word is found, the others keep taking the guesses.
+ */
+
+use crate::game::{Game, GuessError, State};
+use crate::Role;
+
+#[derive(Debug, Clone)]
+pub struct Boards {
+    games: Vec<Game>,
+}
+
+impl Boards {
+    // Games of the same word length and attempts, at least one
+    pub fn new(games: Vec<Game>) -> Self {
+        assert!(!games.is_empty(), "no boards");
+        Boards { games }
+    }
+
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    // First board still being played, the last one if the game is over
+    pub fn current(&self) -> &Game {
+        self.games
+            .iter()
+            .find(|g| g.state() == State::InProgress)
+            .unwrap_or(&self.games[self.games.len() - 1])
+    }
+
+    // Words guessed so far, solved boards have only the beginning of them
+    pub fn guesses(&self) -> Vec<String> {
+        self.games
+            .iter()
+            .max_by_key(|g| g.guesses().len())
+            .map(|g| g.guesses().iter().map(|(w, _)| w.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    // Won when every word is found, the attempt is the last one needed
+    pub fn state(&self) -> State {
+        let mut attempt: usize = 0;
+        for game in &self.games {
+            match game.state() {
+                State::Won(n) => attempt = attempt.max(n),
+                state => return state,
+            }
+        }
+        State::Won(attempt)
+    }
+
+    // Play a guess on every board still in progress, it has to be accepted
+    // by all of them. Returns the feedback per board, None for the frozen ones.
+    pub fn guess(
+        &mut self,
+        word: &str,
+        words: Option<&[String]>,
+    ) -> Result<Vec<Option<Vec<Role>>>, GuessError> {
+        if self.state() != State::InProgress {
+            return Err(GuessError::GameOver);
+        }
+        let mut word: String = word.to_string();
+        for game in self.games.iter().filter(|g| g.state() == State::InProgress) {
+            word = game.check(&word, words)?;
+        }
+        Ok(self
+            .games
+            .iter_mut()
+            .map(|g| match g.state() {
+                State::InProgress => g.guess(&word, None).ok(),
+                _ => None,
+            })
+            .collect())
+    }
+
+    // Best known state of a letter over all boards
+    pub fn letter_role(&self, c: char) -> Role {
+        self.games
+            .iter()
+            .map(|g| g.letter_role(c))
+            .max()
+            .unwrap_or(Role::UnknownYet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use Role::{InPlace as G, NotInWord as B};
+
+    fn boards(secrets: &[&str], tries: usize, hard: bool) -> Boards {
+        Boards::new(
+            secrets
+                .iter()
+                .map(|w| Game::new(w, tries, Rules { hard }))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn solved_boards_freeze() {
+        let mut b = boards(&["CRANE", "SLATE"], 3, false);
+        assert_eq!(
+            b.guess("crane", None),
+            Ok(vec![Some(vec![G; 5]), Some(vec![B, B, G, B, G])])
+        );
+        assert_eq!(b.state(), State::InProgress);
+        assert_eq!(b.current().secret(), "SLATE");
+        assert_eq!(b.guess("SLATE", None), Ok(vec![None, Some(vec![G; 5])]));
+        assert_eq!(b.state(), State::Won(2));
+        assert_eq!(b.guesses(), vec!["CRANE", "SLATE"]);
+        assert_eq!(b.guess("SLATE", None), Err(GuessError::GameOver));
+    }
+
+    #[test]
+    fn lost_if_any_board_is_unsolved() {
+        let mut b = boards(&["CRANE", "SLATE"], 2, false);
+        b.guess("CRANE", None).unwrap();
+        b.guess("TRACE", None).unwrap();
+        assert_eq!(b.state(), State::Lost);
+        assert_eq!(b.games()[0].state(), State::Won(1));
+    }
+
+    #[test]
+    fn every_board_must_accept_the_guess() {
+        let mut b = boards(&["CRANE", "SLATE"], 6, true);
+        b.guess("TRACE", None).unwrap();
+        assert_eq!(
+            b.guess("CRANE", None),
+            Err(GuessError::HardMode("Guess must contain T".to_string()))
+        );
+        assert_eq!(b.guess("CRAN", None), Err(GuessError::Length(5)));
+        assert_eq!(b.guesses().len(), 1);
+        assert_eq!(b.letter_role('T'), Role::NotInPlace);
+    }
+}
@@ -0,0 +1,248 @@
+/*
+ * Game
+ * ----
+ */
+
+use crate::score::{hard_mode_violation, score};
+use crate::solver;
+use crate::Role;
+use std::fmt;
+
+// Rule variants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rules {
+    // Revealed hints must be used in subsequent guesses
+    pub hard: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    InProgress,
+    // Winning attempt number, from 1
+    Won(usize),
+    Lost,
+}
+
+// Reasons a guess is rejected, the game is not changed then
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    Length(usize),
+    NotInDictionary,
+    HardMode(String),
+    GameOver,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::Length(n) => write!(f, "Guess must have {} letters", n),
+            GuessError::NotInDictionary => write!(f, "Not in a dictionary"),
+            GuessError::HardMode(reason) => write!(f, "{}", reason),
+            GuessError::GameOver => write!(f, "The game is over"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    secret: String,
+    // Words the secret may still be in the adversarial mode, where it is not
+    // chosen up front but dodges the guesses. The secret is one of them.
+    candidates: Option<Vec<String>>,
+    tries: usize,
+    rules: Rules,
+    guesses: Vec<(String, Vec<Role>)>,
+}
+
+impl Game {
+    // Words are compared in upper case
+    pub fn new(secret: &str, tries: usize, rules: Rules) -> Self {
+        Game {
+            secret: secret.to_uppercase(),
+            candidates: None,
+            tries,
+            rules,
+            guesses: Vec::new(),
+        }
+    }
+
+    // Adversarial game, every guess gets the feedback keeping the most of
+    // `candidates` (upper case words of the same length) possible
+    pub fn adversarial(candidates: Vec<String>, tries: usize, rules: Rules) -> Self {
+        let mut game: Game = Game::new(candidates.first().map_or("", |w| w.as_str()), tries, rules);
+        game.candidates = Some(candidates);
+        game
+    }
+
+    pub fn is_adversarial(&self) -> bool {
+        self.candidates.is_some()
+    }
+
+    // Words the secret may still be, None if it is chosen up front
+    pub fn candidates(&self) -> Option<&[String]> {
+        self.candidates.as_deref()
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn word_len(&self) -> usize {
+        self.secret.chars().count()
+    }
+
+    pub fn tries(&self) -> usize {
+        self.tries
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    // Accepted guesses with their feedback
+    pub fn guesses(&self) -> &[(String, Vec<Role>)] {
+        &self.guesses
+    }
+
+    pub fn state(&self) -> State {
+        match self.guesses.last() {
+            Some((w, _)) if *w == self.secret => State::Won(self.guesses.len()),
+            _ if self.guesses.len() >= self.tries => State::Lost,
+            _ => State::InProgress,
+        }
+    }
+
+    // Check a guess without playing it, returns the word in upper case.
+    // `words` is the dictionary of accepted guesses, None accepts any word
+    // of the right length.
+    pub fn check(&self, word: &str, words: Option<&[String]>) -> Result<String, GuessError> {
+        if self.state() != State::InProgress {
+            return Err(GuessError::GameOver);
+        }
+        let word: String = word.to_uppercase();
+        if word.chars().count() != self.word_len() {
+            return Err(GuessError::Length(self.word_len()));
+        }
+        if let Some(words) = words {
+            if !words.contains(&word) {
+                return Err(GuessError::NotInDictionary);
+            }
+        }
+        if self.rules.hard {
+            if let Some(reason) = hard_mode_violation(&self.guesses, &word) {
+                return Err(GuessError::HardMode(reason));
+            }
+        }
+        Ok(word)
+    }
+
+    // Check and score a guess, see check()
+    pub fn guess(&mut self, word: &str, words: Option<&[String]>) -> Result<Vec<Role>, GuessError> {
+        let word: String = self.check(word, words)?;
+        let roles: Vec<Role> = match &mut self.candidates {
+            Some(candidates) => {
+                let (roles, left) = solver::dodge(&word, candidates);
+                *candidates = left;
+                self.secret = candidates[0].clone();
+                roles
+            }
+            None => score(&word, &self.secret),
+        };
+        self.guesses.push((word, roles.clone()));
+        Ok(roles)
+    }
+
+    // Best known state of a letter over all guesses
+    pub fn letter_role(&self, c: char) -> Role {
+        let c: char = c.to_ascii_uppercase();
+        self.guesses
+            .iter()
+            .flat_map(|(w, roles)| w.chars().zip(roles.iter().copied()))
+            .filter(|(x, _)| *x == c)
+            .map(|(_, role)| role)
+            .max()
+            .unwrap_or(Role::UnknownYet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Role::{InPlace as G, NotInPlace as Y, NotInWord as B};
+
+    fn words() -> Vec<String> {
+        ["CRANE", "SLATE", "TRACE", "CRATE", "ABBEY"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn win() {
+        let mut game = Game::new("crane", 6, Rules::default());
+        assert_eq!(game.guess("slate", Some(&words())), Ok(vec![B, B, G, B, G]));
+        assert_eq!(game.state(), State::InProgress);
+        assert_eq!(game.guess("CRANE", Some(&words())), Ok(vec![G; 5]));
+        assert_eq!(game.state(), State::Won(2));
+        assert_eq!(game.guess("TRACE", None), Err(GuessError::GameOver));
+        assert_eq!(game.guesses().len(), 2);
+    }
+
+    #[test]
+    fn loss() {
+        let mut game = Game::new("CRANE", 2, Rules::default());
+        game.guess("SLATE", None).unwrap();
+        assert_eq!(game.state(), State::InProgress);
+        game.guess("TRACE", None).unwrap();
+        assert_eq!(game.state(), State::Lost);
+    }
+
+    #[test]
+    fn rejected_guesses_are_not_counted() {
+        let mut game = Game::new("CRANE", 6, Rules { hard: true });
+        assert_eq!(game.guess("SLAT", None), Err(GuessError::Length(5)));
+        assert_eq!(
+            game.guess("XXXXX", Some(&words())),
+            Err(GuessError::NotInDictionary)
+        );
+        game.guess("TRACE", None).unwrap();
+        assert_eq!(
+            game.guess("SLATE", None),
+            Err(GuessError::HardMode("2nd letter must be R".to_string()))
+        );
+        assert_eq!(game.guesses().len(), 1);
+        assert_eq!(game.state(), State::InProgress);
+    }
+
+    #[test]
+    fn adversarial() {
+        let words: Vec<String> = ["BAKER", "CAKES", "FAKER", "MAKER", "TAKER"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let mut game = Game::adversarial(words.clone(), 6, Rules::default());
+        assert_eq!(game.word_len(), 5);
+        assert_eq!(game.guess("FAKER", None), Ok(vec![B, G, G, G, G]));
+        assert_eq!(game.candidates().unwrap().len(), 3);
+        game.guess("BAKER", None).unwrap();
+        game.guess("MAKER", None).unwrap();
+        assert_eq!(game.state(), State::InProgress);
+        assert_eq!(game.secret(), "TAKER");
+        assert_eq!(game.guess("taker", Some(&words)), Ok(vec![G; 5]));
+        assert_eq!(game.state(), State::Won(4));
+    }
+
+    #[test]
+    fn letter_roles() {
+        let mut game = Game::new("CRANE", 6, Rules::default());
+        game.guess("TRACE", None).unwrap();
+        assert_eq!(game.letter_role('C'), Y);
+        game.guess("CRATE", None).unwrap();
+        assert_eq!(game.letter_role('c'), G);
+        assert_eq!(game.letter_role('T'), B);
+        assert_eq!(game.letter_role('E'), G);
+        assert_eq!(game.letter_role('Z'), Role::UnknownYet);
+    }
+}
@@ -0,0 +1,29 @@
+/*
+ * Wordle game engine
+ * ------------------
+ * Game rules without any terminal dependency, the ncurses program is one
+ * front-end over it. Bots and tests can play a `Game` directly:
+ *
+ *   let mut game = Game::new("CRANE", 6, Rules::default());
+ *   game.guess("SLATE", None).unwrap();
+ *   assert_eq!(game.state(), State::InProgress);
+ */
+
+mod boards;
+mod game;
+mod score;
+pub mod solver;
+pub mod words;
+
+pub use boards::Boards;
+pub use game::{Game, GuessError, Rules, State};
+pub use score::{hard_mode_violation, score};
+
+// Ordered by the amount of information about a letter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    UnknownYet,
+    NotInWord,
+    NotInPlace,
+    InPlace,
+}
@@ -0,0 +1,252 @@
+/*
+ * Scoring
+ * -------
+ */
+
+use crate::Role;
+
+// Score a guess against the target word the way the original game does:
+// exact matches are taken first, then remaining letters are marked as
+// misplaced only while unmatched copies of them are left in the target.
+pub fn score(guess: &str, target: &str) -> Vec<Role> {
+    let guess: Vec<char> = guess.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+    let mut roles: Vec<Role> = vec![Role::NotInWord; guess.len()];
+    let mut unmatched: Vec<char> = Vec::new();
+
+    // First pass - letters in a correct place
+    for (idx, ch) in guess.iter().enumerate() {
+        if target.get(idx) == Some(ch) {
+            roles[idx] = Role::InPlace;
+        } else if let Some(t) = target.get(idx) {
+            unmatched.push(*t);
+        }
+    }
+    if target.len() > guess.len() {
+        unmatched.extend_from_slice(&target[guess.len()..]);
+    }
+
+    // Second pass - letters in a wrong place, each target letter used once
+    for (idx, ch) in guess.iter().enumerate() {
+        if roles[idx] == Role::InPlace {
+            continue;
+        }
+        if let Some(pos) = unmatched.iter().position(|t| t == ch) {
+            unmatched.swap_remove(pos);
+            roles[idx] = Role::NotInPlace;
+        }
+    }
+    roles
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+// Hard mode check. Every letter already revealed in a correct place must stay
+// there and every revealed letter must be reused (as many times as it was
+// revealed in a single row). Returns the explanation of the first violated
+// rule or None if the guess is acceptable.
+pub fn hard_mode_violation(history: &[(String, Vec<Role>)], guess: &str) -> Option<String> {
+    let guess: Vec<char> = guess.chars().collect();
+
+    for (word, roles) in history {
+        for (idx, (ch, role)) in word.chars().zip(roles.iter()).enumerate() {
+            if *role == Role::InPlace && guess.get(idx) != Some(&ch) {
+                return Some(format!("{} letter must be {}", ordinal(idx + 1), ch));
+            }
+        }
+    }
+
+    let mut required: Vec<(char, usize)> = Vec::new();
+    for (word, roles) in history {
+        let mut row: Vec<(char, usize)> = Vec::new();
+        for (ch, role) in word.chars().zip(roles.iter()) {
+            if *role == Role::InPlace || *role == Role::NotInPlace {
+                match row.iter_mut().find(|(c, _)| *c == ch) {
+                    Some((_, n)) => *n += 1,
+                    None => row.push((ch, 1)),
+                }
+            }
+        }
+        for (ch, n) in row {
+            match required.iter_mut().find(|(c, _)| *c == ch) {
+                Some((_, m)) => *m = (*m).max(n),
+                None => required.push((ch, n)),
+            }
+        }
+    }
+    for (ch, n) in required {
+        let found = guess.iter().filter(|&&c| c == ch).count();
+        if found < n {
+            return Some(if n == 1 {
+                format!("Guess must contain {}", ch)
+            } else {
+                format!("Guess must contain {} {} letters", n, ch)
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Role::{InPlace as G, NotInPlace as Y, NotInWord as B};
+
+    #[test]
+    fn all_correct() {
+        assert_eq!(score("CRANE", "CRANE"), vec![G, G, G, G, G]);
+    }
+
+    #[test]
+    fn nothing_matches() {
+        assert_eq!(score("BUILT", "CRANE"), vec![B, B, B, B, B]);
+    }
+
+    #[test]
+    fn all_misplaced() {
+        assert_eq!(score("ABCDE", "EABCD"), vec![Y, Y, Y, Y, Y]);
+    }
+
+    #[test]
+    fn mixed_unique_letters() {
+        assert_eq!(score("CRATE", "TRACE"), vec![Y, G, G, Y, G]);
+    }
+
+    #[test]
+    fn repeated_guess_letter_single_in_target() {
+        // THEME has two E's: one matched in place, only one left for a yellow
+        assert_eq!(score("EERIE", "THEME"), vec![Y, B, B, B, G]);
+        assert_eq!(score("GEESE", "THEME"), vec![B, B, G, B, G]);
+        assert_eq!(score("SPEED", "ABIDE"), vec![B, B, Y, B, Y]);
+    }
+
+    #[test]
+    fn repeated_guess_letter_two_in_target() {
+        assert_eq!(score("EEJIT", "THEME"), vec![Y, Y, B, B, Y]);
+    }
+
+    #[test]
+    fn green_takes_priority_over_earlier_yellow() {
+        // The single L of the target is matched in place, the first L is grey
+        assert_eq!(score("LLAMA", "HELLO"), vec![Y, Y, B, B, B]);
+        assert_eq!(score("HALLS", "HELLO"), vec![G, B, G, G, B]);
+        assert_eq!(score("SKILL", "SPELL"), vec![G, B, B, G, G]);
+        assert_eq!(score("LOLLY", "SPELL"), vec![Y, B, B, G, B]);
+    }
+
+    #[test]
+    fn yellow_limited_by_target_count() {
+        assert_eq!(score("SASSY", "ASSET"), vec![Y, Y, G, B, B]);
+        assert_eq!(score("ERROR", "RIVER"), vec![Y, Y, B, B, G]);
+        assert_eq!(score("OOOOO", "ROBOT"), vec![B, G, B, G, B]);
+    }
+
+    #[test]
+    fn repeated_target_letter_single_in_guess() {
+        assert_eq!(score("CLEAN", "EERIE"), vec![B, B, Y, B, B]);
+        assert_eq!(score("ABBEY", "KEBAB"), vec![Y, Y, G, Y, B]);
+    }
+
+    #[test]
+    fn same_letter_everywhere() {
+        assert_eq!(score("AAAAA", "ABACA"), vec![G, B, G, B, G]);
+        assert_eq!(score("AAAAA", "BBBBB"), vec![B, B, B, B, B]);
+        assert_eq!(score("BBBBB", "BBBBB"), vec![G, G, G, G, G]);
+    }
+
+    #[test]
+    fn misplaced_uses_left_to_right_order() {
+        assert_eq!(score("ALLEE", "LEAVE"), vec![Y, Y, B, Y, G]);
+        assert_eq!(score("EEEEL", "LEVEE"), vec![Y, G, B, G, Y]);
+    }
+
+    #[test]
+    fn other_word_lengths() {
+        assert_eq!(score("AB", "BA"), vec![Y, Y]);
+        assert_eq!(score("A", "A"), vec![G]);
+        assert_eq!(score("BANANAS", "ANANASB"), vec![Y, Y, Y, Y, Y, Y, Y]);
+        assert_eq!(score("", ""), vec![]);
+    }
+
+    #[test]
+    fn exhaustive_small_alphabet() {
+        // Compare against a straightforward reference implementation over
+        // every word of length 4 built from a small alphabet
+        fn reference(guess: &[char], target: &[char]) -> Vec<Role> {
+            let mut roles = vec![B; guess.len()];
+            let mut used = vec![false; target.len()];
+            for i in 0..guess.len() {
+                if guess[i] == target[i] {
+                    roles[i] = G;
+                    used[i] = true;
+                }
+            }
+            for i in 0..guess.len() {
+                if roles[i] == G {
+                    continue;
+                }
+                for j in 0..target.len() {
+                    if !used[j] && guess[i] == target[j] {
+                        used[j] = true;
+                        roles[i] = Y;
+                        break;
+                    }
+                }
+            }
+            roles
+        }
+        let alphabet = ['A', 'B', 'C'];
+        let mut words: Vec<String> = vec![String::new()];
+        for _ in 0..4 {
+            words = words
+                .iter()
+                .flat_map(|w| alphabet.iter().map(move |c| format!("{}{}", w, c)))
+                .collect();
+        }
+        for g in &words {
+            for t in &words {
+                let gc: Vec<char> = g.chars().collect();
+                let tc: Vec<char> = t.chars().collect();
+                assert_eq!(score(g, t), reference(&gc, &tc), "{} vs {}", g, t);
+            }
+        }
+    }
+
+    #[test]
+    fn hard_mode_rules() {
+        let history = vec![("CRANE".to_string(), score("CRANE", "SCARE"))];
+        assert_eq!(hard_mode_violation(&history, "SCARE"), None);
+        assert_eq!(
+            hard_mode_violation(&history, "CLOTH"),
+            Some("3rd letter must be A".to_string())
+        );
+        assert_eq!(
+            hard_mode_violation(&history, "FLAKE"),
+            Some("Guess must contain C".to_string())
+        );
+        assert_eq!(hard_mode_violation(&[], "ZZZZZ"), None);
+    }
+
+    #[test]
+    fn hard_mode_repeated_letters() {
+        let history = vec![("SASSY".to_string(), score("SASSY", "ASSET"))];
+        assert_eq!(hard_mode_violation(&history, "ASSET"), None);
+        assert_eq!(
+            hard_mode_violation(&history, "BASTE"),
+            Some("Guess must contain 2 S letters".to_string())
+        );
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(22), "22nd");
+    }
+}
@@ -0,0 +1,286 @@
+/*
+ * Solver
+ * ------
+ * Suggests the guess which gives the most information about the secret
+ * word, i.e. maximizes the entropy of the feedback over the candidates.
+ */
+
+use crate::score::{hard_mode_violation, score};
+use crate::Role;
+use std::collections::HashMap;
+use std::thread;
+
+// Candidates the entropy is estimated on, evenly picked from larger sets
+const SAMPLE: usize = 1000;
+// Feedback patterns are counted in an array up to this word length
+const DENSE_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub word: String,
+    // Expected information of the guess in bits
+    pub bits: f64,
+}
+
+// Same feedback as score() packed as a base 3 number, 0 - not in word,
+// 1 - not in place, 2 - in place. Words are upper case ASCII, the low five
+// bits of a letter are enough to tell letters apart.
+pub(crate) fn pattern(guess: &[u8], target: &[u8]) -> u64 {
+    let mut unmatched: [u8; 32] = [0; 32];
+    let mut in_place: u64 = 0;
+    for (idx, (&g, &t)) in guess.iter().zip(target.iter()).enumerate() {
+        if g == t {
+            in_place |= 1 << idx;
+        } else {
+            unmatched[(t & 31) as usize] += 1;
+        }
+    }
+    let mut packed: u64 = 0;
+    for (idx, &g) in guess.iter().enumerate() {
+        let digit: u64 = if in_place & (1 << idx) != 0 {
+            2
+        } else {
+            let n = &mut unmatched[(g & 31) as usize];
+            if *n > 0 {
+                *n -= 1;
+                1
+            } else {
+                0
+            }
+        };
+        packed = packed * 3 + digit;
+    }
+    packed
+}
+
+fn packed_roles(roles: &[Role]) -> u64 {
+    roles.iter().fold(0, |packed, role| {
+        packed * 3
+            + match role {
+                Role::InPlace => 2,
+                Role::NotInPlace => 1,
+                _ => 0,
+            }
+    })
+}
+
+// Words still possible as the secret after the guesses with their feedback
+pub fn candidates(history: &[(String, Vec<Role>)], words: &[String]) -> Vec<String> {
+    let history: Vec<(&[u8], u64)> = history
+        .iter()
+        .map(|(w, roles)| (w.as_bytes(), packed_roles(roles)))
+        .collect();
+    words
+        .iter()
+        .filter(|w| {
+            history
+                .iter()
+                .all(|(g, p)| g.len() == w.len() && pattern(g, w.as_bytes()) == *p)
+        })
+        .cloned()
+        .collect()
+}
+
+// Split the candidates by the feedback on the guess and keep the largest
+// group, ties are resolved in favor of the less revealing feedback. Returns
+// the feedback and the words left.
+pub fn dodge(guess: &str, candidates: &[String]) -> (Vec<Role>, Vec<String>) {
+    let mut groups: HashMap<u64, Vec<String>> = HashMap::new();
+    for w in candidates {
+        groups
+            .entry(pattern(guess.as_bytes(), w.as_bytes()))
+            .or_default()
+            .push(w.clone());
+    }
+    match groups
+        .into_iter()
+        .max_by(|a, b| a.1.len().cmp(&b.1.len()).then(b.0.cmp(&a.0)))
+    {
+        Some((_, words)) => (score(guess, &words[0]), words),
+        None => (score(guess, guess), Vec::new()),
+    }
+}
+
+fn entropy(guess: &[u8], sample: &[&[u8]], counts: &mut Vec<u32>, patterns: &mut Vec<u64>) -> f64 {
+    let total: f64 = sample.len() as f64;
+    let mut sum: f64 = 0.0;
+    let mut add = |n: u32| {
+        if n > 0 {
+            let p: f64 = n as f64 / total;
+            sum -= p * p.log2();
+        }
+    };
+    if guess.len() <= DENSE_LEN {
+        counts.clear();
+        counts.resize(3usize.pow(guess.len() as u32), 0);
+        for t in sample {
+            counts[pattern(guess, t) as usize] += 1;
+        }
+        counts.iter().for_each(|&n| add(n));
+    } else {
+        patterns.clear();
+        patterns.extend(sample.iter().map(|t| pattern(guess, t)));
+        patterns.sort_unstable();
+        let mut run: u32 = 0;
+        for (idx, p) in patterns.iter().enumerate() {
+            run += 1;
+            if patterns.get(idx + 1) != Some(p) {
+                add(run);
+                run = 0;
+            }
+        }
+    }
+    sum
+}
+
+// Best guess from `pool` for the remaining `candidates` (sorted). Ties are resolved in
+// favor of the candidates, they can win right away. In the hard mode only
+// guesses following the revealed hints of `history` are considered.
+pub fn best_guess(
+    pool: &[String],
+    candidates: &[String],
+    hard: bool,
+    history: &[(String, Vec<Role>)],
+) -> Option<Hint> {
+    let threads: usize = thread::available_parallelism().map_or(1, |n| n.get());
+    best_guess_threads(pool, candidates, hard, history, threads)
+}
+
+// Same as best_guess() but the work is split between the given amount of
+// threads, e.g. one when games are already played in parallel
+pub fn best_guess_threads(
+    pool: &[String],
+    candidates: &[String],
+    hard: bool,
+    history: &[(String, Vec<Role>)],
+    threads: usize,
+) -> Option<Hint> {
+    if candidates.len() <= 2 {
+        return candidates.first().map(|w| Hint {
+            word: w.clone(),
+            bits: if candidates.len() == 2 { 1.0 } else { 0.0 },
+        });
+    }
+    let step: usize = candidates.len().div_ceil(SAMPLE);
+    let sample: Vec<&[u8]> = candidates
+        .iter()
+        .step_by(step)
+        .map(|w| w.as_bytes())
+        .collect();
+    let mut pool: Vec<&String> = pool
+        .iter()
+        .chain(candidates.iter())
+        .filter(|w| w.len() == sample[0].len())
+        .filter(|w| !hard || hard_mode_violation(history, w).is_none())
+        .collect();
+    pool.sort_unstable();
+    pool.dedup();
+
+    let chunk: usize = pool.len().div_ceil(threads.max(1)).max(1);
+    let rated: Vec<(f64, bool, &String)> = thread::scope(|scope| {
+        let handles: Vec<_> = pool
+            .chunks(chunk)
+            .map(|part| {
+                let sample = &sample;
+                scope.spawn(move || {
+                    let mut counts: Vec<u32> = Vec::new();
+                    let mut patterns: Vec<u64> = Vec::new();
+                    part.iter()
+                        .map(|w| {
+                            let bits = entropy(w.as_bytes(), sample, &mut counts, &mut patterns);
+                            (bits, candidates.binary_search(w).is_ok(), *w)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    });
+    rated
+        .into_iter()
+        .max_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .unwrap()
+                .then(a.1.cmp(&b.1))
+                .then(b.2.cmp(a.2))
+        })
+        .map(|(bits, _, w)| Hint {
+            word: w.clone(),
+            bits,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score;
+
+    fn list(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn pattern_matches_score() {
+        let words = [
+            "CRANE", "SLATE", "EERIE", "THEME", "LEVEE", "EEEEL", "ABBEY", "BABES",
+        ];
+        for g in words {
+            for t in words {
+                assert_eq!(
+                    pattern(g.as_bytes(), t.as_bytes()),
+                    packed_roles(&score(g, t)),
+                    "{} {}",
+                    g,
+                    t
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn filters_candidates() {
+        let words = list(&["CRANE", "CRATE", "SLATE", "TRACE", "GRATE"]);
+        let history = vec![("TRACE".to_string(), score("TRACE", "CRATE"))];
+        assert_eq!(candidates(&history, &words), list(&["CRATE"]));
+        assert_eq!(candidates(&[], &words), words);
+    }
+
+    #[test]
+    fn splits_candidates() {
+        // Only a guess with both B and D tells all four apart
+        let cands = list(&["ABXYZ", "ADXYZ", "ACXYZ", "AEXYZ"]);
+        let pool = list(&["BDQQQ", "QQQQQ"]);
+        let hint = best_guess(&pool, &cands, false, &[]).unwrap();
+        assert_eq!(hint.word, "BDQQQ");
+        assert!(hint.bits > 1.4);
+        let hint = best_guess(&pool, &cands[..1], false, &[]).unwrap();
+        assert_eq!((hint.word.as_str(), hint.bits), ("ABXYZ", 0.0));
+        assert_eq!(best_guess(&pool, &[], false, &[]), None);
+    }
+
+    #[test]
+    fn dodges_guesses() {
+        use Role::{InPlace as G, NotInWord as B};
+        let words = list(&["BAKER", "CAKES", "FAKER", "MAKER", "TAKER"]);
+        let (roles, left) = dodge("FAKER", &words);
+        assert_eq!(roles, vec![B, G, G, G, G]);
+        assert_eq!(left, list(&["BAKER", "MAKER", "TAKER"]));
+        // Equal groups, the one with less letters in place is kept
+        let (roles, left) = dodge("MAKER", &left[..2]);
+        assert_eq!((roles, left), (vec![B, G, G, G, G], list(&["BAKER"])));
+        let (roles, left) = dodge("MAKER", &list(&["MAKER"]));
+        assert_eq!((roles, left), (vec![G; 5], list(&["MAKER"])));
+    }
+
+    #[test]
+    fn hard_mode_pool() {
+        let cands = list(&["ABXYZ", "ADXYZ", "ACXYZ", "AEXYZ"]);
+        let pool = list(&["BDQQQ"]);
+        let history = vec![("AQQQQ".to_string(), score("AQQQQ", "ABXYZ"))];
+        let hint = best_guess(&pool, &cands, true, &history).unwrap();
+        assert!(hint.word.starts_with('A'));
+    }
+}
@@ -0,0 +1,31 @@
+/*
+ * Word lists
+ * ----------
+ */
+
+// Extract words of the requested length from a word list text: one word per
+// line, case insensitive, CR line endings and non alphabetic entries ignored.
+pub fn parse(text: &str, word_len: i32) -> Vec<String> {
+    let mut words: Vec<String> = text
+        .lines()
+        .map(|x| x.trim())
+        .filter(|x| x.len() == word_len as usize && x.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|x| x.to_uppercase())
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_normalizes_entries() {
+        let text = "crane\r\nSLATE\n  trace \nit's\ncafé\nab-cd\n\nCrane\nlonger\n";
+        assert_eq!(parse(text, 5), vec!["CRANE", "SLATE", "TRACE"]);
+        assert_eq!(parse(text, 6), vec!["LONGER"]);
+        assert!(parse(text, 7).is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+/*
+ * Benchmark
+ * ---------
+ * Autoplay of every answer with a built-in strategy. A game goes on after
+ * the last attempt until the word is guessed, so the worst case is known.
+ */
+
+use crate::error::MAX_TRIES;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::thread;
+use wordle_engine::{solver, Game, Role, Rules, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // Maximal entropy guess from the whole dictionary
+    Entropy,
+    // First remaining candidate in the alphabetical order
+    First,
+    // Random remaining candidate
+    Random,
+}
+
+pub const STRATEGIES: &[&str] = &["entropy", "first", "random"];
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "entropy" => Ok(Strategy::Entropy),
+            "first" => Ok(Strategy::First),
+            "random" => Ok(Strategy::Random),
+            _ => Err(format!("Unknown strategy \"{}\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Strategy::Entropy => "entropy",
+                Strategy::First => "first",
+                Strategy::Random => "random",
+            }
+        )
+    }
+}
+
+pub struct Setup<'a> {
+    pub strategy: Strategy,
+    // First guess of every game, chosen by the strategy if None
+    pub start: Option<String>,
+    pub hard: bool,
+    pub seed: u64,
+    // Words accepted as guesses
+    pub words: &'a [String],
+    // Secret words, sorted
+    pub answers: &'a [String],
+}
+
+// Entropy strategy guesses by the game history, they don't depend on the secret word
+pub type Cache = Mutex<HashMap<Vec<(String, Vec<Role>)>, String>>;
+
+fn next_guess(
+    setup: &Setup,
+    game: &Game,
+    candidates: &[String],
+    rng: &mut StdRng,
+    cache: &Cache,
+) -> String {
+    if let (Some(w), true) = (&setup.start, game.guesses().is_empty()) {
+        return w.clone();
+    }
+    match setup.strategy {
+        Strategy::Entropy => {
+            if let Some(w) = cache.lock().unwrap().get(game.guesses()) {
+                return w.clone();
+            }
+            let w: String =
+                solver::best_guess_threads(setup.words, candidates, setup.hard, game.guesses(), 1)
+                    .map(|hint| hint.word)
+                    .unwrap_or_else(|| candidates[0].clone());
+            cache
+                .lock()
+                .unwrap()
+                .insert(game.guesses().to_vec(), w.clone());
+            w
+        }
+        Strategy::First => candidates[0].clone(),
+        Strategy::Random => candidates[rng.gen_range(0, candidates.len())].clone(),
+    }
+}
+
+// Number of guesses used to find `secret`, None if it wasn't found in
+// MAX_TRIES guesses. `idx` makes random choices reproducible.
+pub fn play(setup: &Setup, secret: &str, idx: usize, cache: &Cache) -> Option<usize> {
+    let mut rng: StdRng = StdRng::seed_from_u64(setup.seed ^ idx as u64);
+    let mut game: Game = Game::new(secret, MAX_TRIES as usize, Rules { hard: setup.hard });
+    let mut candidates: Vec<String> = setup.answers.to_vec();
+    while game.state() == State::InProgress && !candidates.is_empty() {
+        let guess: String = next_guess(setup, &game, &candidates, &mut rng, cache);
+        if game.guess(&guess, None).is_err() {
+            return None;
+        }
+        // The previous candidates already match the earlier guesses
+        let last = &game.guesses()[game.guesses().len() - 1..];
+        candidates = solver::candidates(last, &candidates);
+    }
+    match game.state() {
+        State::Won(n) => Some(n),
+        _ => None,
+    }
+}
+
+// Play every answer, games are split between `threads` threads
+pub fn run(setup: &Setup, threads: usize) -> Vec<Option<usize>> {
+    let threads: usize = threads.clamp(1, setup.answers.len().max(1));
+    let mut results: Vec<Option<usize>> = vec![None; setup.answers.len()];
+    let cache: Cache = Mutex::new(HashMap::new());
+    let cache: &Cache = &cache;
+    let parts: Vec<Vec<(usize, Option<usize>)>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                scope.spawn(move || {
+                    // Interleaved to even out slow and fast words
+                    (t..setup.answers.len())
+                        .step_by(threads)
+                        .map(|idx| (idx, play(setup, &setup.answers[idx], idx, cache)))
+                        .collect()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for (idx, result) in parts.into_iter().flatten() {
+        results[idx] = result;
+    }
+    results
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub games: usize,
+    pub won: usize,
+    // Guesses used on won games
+    pub mean: f64,
+    // Most guesses used, None if some word wasn't found at all
+    pub worst: Option<usize>,
+    // Wins by the number of guesses, index 0 is a win from the first guess
+    pub hist: Vec<usize>,
+}
+
+impl Summary {
+    pub fn new(results: &[Option<usize>], tries: usize) -> Self {
+        let mut hist: Vec<usize> = vec![0; tries];
+        let mut total: usize = 0;
+        for n in results.iter().flatten() {
+            if *n <= tries {
+                hist[n - 1] += 1;
+                total += n;
+            }
+        }
+        let won: usize = hist.iter().sum();
+        Summary {
+            games: results.len(),
+            won,
+            mean: if won > 0 {
+                total as f64 / won as f64
+            } else {
+                0.0
+            },
+            worst: results
+                .iter()
+                .try_fold(0, |worst, n| n.map(|n| worst.max(n))),
+            hist,
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games > 0 {
+            self.won as f64 * 100.0 / self.games as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn worst_text(&self) -> String {
+        match self.worst {
+            Some(n) => n.to_string(),
+            None => "X".to_string(),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text: String = format!(
+            "Won: {}/{} ({:.1}%)\nMean guesses: {:.3}\nWorst case: {}\nGuess distribution:\n",
+            self.won,
+            self.games,
+            self.win_rate(),
+            self.mean,
+            self.worst_text()
+        );
+        let max: usize = self.hist.iter().copied().max().unwrap_or(0).max(1);
+        for (idx, n) in self.hist.iter().enumerate() {
+            text.push_str(&format!(
+                "{:>3} {:>6} {}\n",
+                idx + 1,
+                n,
+                "#".repeat(n * 40 / max)
+            ));
+        }
+        text.push_str(&format!("  X {:>6}\n", self.games - self.won));
+        text
+    }
+
+    pub fn csv_header(tries: usize) -> String {
+        let hist: Vec<String> = (1..=tries).map(|n| format!("guess_{}", n)).collect();
+        format!(
+            "strategy,start,wlen,tries,hard,games,won,win_rate,mean_guesses,worst,{},lost",
+            hist.join(",")
+        )
+    }
+
+    pub fn csv_row(&self, setup: &Setup, wlen: i32) -> String {
+        let hist: Vec<String> = self.hist.iter().map(|n| n.to_string()).collect();
+        format!(
+            "{},{},{},{},{},{},{},{:.2},{:.4},{},{},{}",
+            setup.strategy,
+            setup.start.as_deref().unwrap_or(""),
+            wlen,
+            self.hist.len(),
+            setup.hard,
+            self.games,
+            self.won,
+            self.win_rate(),
+            self.mean,
+            self.worst_text(),
+            hist.join(","),
+            self.games - self.won
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn first_strategy() {
+        let answers = list(&["BAKER", "CAKES", "FAKER", "MAKER", "TAKER"]);
+        let setup = Setup {
+            strategy: Strategy::First,
+            start: None,
+            hard: false,
+            seed: 0,
+            words: &answers,
+            answers: &answers,
+        };
+        // BAKER leaves CAKES alone, FAKER, MAKER and TAKER differ in the first letter only
+        assert_eq!(
+            run(&setup, 2),
+            vec![Some(1), Some(2), Some(2), Some(3), Some(4)]
+        );
+        let setup = Setup {
+            start: Some("CAKES".to_string()),
+            ..setup
+        };
+        assert_eq!(play(&setup, "CAKES", 0, &Cache::default()), Some(1));
+        assert_eq!(play(&setup, "BAKER", 0, &Cache::default()), Some(2));
+    }
+
+    #[test]
+    fn entropy_and_random_find_every_word() {
+        let answers = list(&["ABXYZ", "ACXYZ", "ADXYZ", "AEXYZ", "AFXYZ"]);
+        let mut words = list(&["BCDEQ"]);
+        words.extend(answers.iter().cloned());
+        for strategy in [Strategy::Entropy, Strategy::Random] {
+            let setup = Setup {
+                strategy,
+                start: None,
+                hard: false,
+                seed: 7,
+                words: &words,
+                answers: &answers,
+            };
+            let results = run(&setup, 3);
+            assert!(results.iter().all(|r| r.is_some()), "{}", strategy);
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let s = Summary::new(&[Some(2), Some(3), Some(3), Some(7), None], 6);
+        assert_eq!(s.hist, vec![0, 1, 2, 0, 0, 0]);
+        assert_eq!((s.games, s.won, s.worst), (5, 3, None));
+        assert!((s.mean - 8.0 / 3.0).abs() < 1e-9);
+        assert_eq!(Summary::new(&[Some(2), Some(7)], 6).worst, Some(7));
+        assert_eq!(
+            Summary::csv_header(2),
+            "strategy,start,wlen,tries,hard,games,won,win_rate,mean_guesses,worst,guess_1,guess_2,lost"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+/*
+ * Candidates browser
+ * ------------------
+ * Scrollable panel with the words still matching the revealed hints,
+ * typed letters narrow the list down to words with such a prefix.
+ */
+
+extern crate ncurses;
+
+use crate::utils::{self, Win};
+use ncurses::*;
+
+const MAX_WIDTH: i32 = 72;
+const MAX_HEIGHT: i32 = 24;
+// Header, empty line and footer around the list
+const EXTRA_LINES: i32 = 3;
+const FOOTER: &str = "Arrows, PgUp, PgDn - scroll, letters - filter, Esc - close";
+
+pub struct Browser<'a> {
+    words: &'a [String],
+    prefix: String,
+    // First visible row of the list
+    top: usize,
+    win: Win,
+}
+
+impl<'a> Browser<'a> {
+    fn create_win() -> Win {
+        let width: i32 = COLS().min(MAX_WIDTH);
+        let height: i32 = LINES().min(MAX_HEIGHT);
+        Win::new(
+            "Candidates",
+            false,
+            height,
+            width,
+            (COLS() - width) / 2,
+            (LINES() - height) / 2,
+        )
+    }
+
+    pub fn new(words: &'a [String]) -> Self {
+        Browser {
+            words,
+            prefix: String::new(),
+            top: 0,
+            win: Browser::create_win(),
+        }
+    }
+
+    fn filtered(&self) -> Vec<&'a String> {
+        self.words
+            .iter()
+            .filter(|w| w.starts_with(&self.prefix))
+            .collect()
+    }
+
+    // Words per row
+    fn columns(&self) -> usize {
+        let inner: i32 = self.win_width() - utils::LEFT_BW - utils::RIGHT_BW - 2;
+        let word_len: i32 = self.words.first().map_or(1, |w| w.len() as i32);
+        std::cmp::max(1, (inner + 1) / (word_len + 1)) as usize
+    }
+
+    // Rows visible at once
+    fn page(&self) -> usize {
+        std::cmp::max(
+            1,
+            self.win_height() - utils::TOP_BW - utils::BOT_BW - EXTRA_LINES,
+        ) as usize
+    }
+
+    fn win_width(&self) -> i32 {
+        getmaxx(self.win.w)
+    }
+
+    fn win_height(&self) -> i32 {
+        getmaxy(self.win.w)
+    }
+
+    fn scroll(&mut self, rows: isize) {
+        let total: usize = self.filtered().len().div_ceil(self.columns());
+        let last: usize = total.saturating_sub(self.page());
+        self.top = (self.top as isize + rows).clamp(0, last as isize) as usize;
+    }
+
+    fn draw(&mut self) {
+        let words: Vec<&'a String> = self.filtered();
+        let columns: usize = self.columns();
+        let page: usize = self.page();
+        let header: String = if self.prefix.is_empty() {
+            format!("Words left: {}", words.len())
+        } else {
+            format!(
+                "Words left: {}, starting with {}: {}",
+                self.words.len(),
+                self.prefix,
+                words.len()
+            )
+        };
+        self.win.erase();
+        self.win.print(false, 1, 0, utils::HELP_COLOR, &header);
+        for (y, row) in words.chunks(columns).skip(self.top).take(page).enumerate() {
+            let line: Vec<&str> = row.iter().map(|w| w.as_str()).collect();
+            self.win
+                .print(false, 1, 2 + y as i32, utils::NORM_COLOR, &line.join(" "));
+        }
+        let footer: &str = if (self.win_width() as usize) < FOOTER.len() + 4 {
+            "Esc - close"
+        } else {
+            FOOTER
+        };
+        self.win.print(
+            false,
+            1,
+            page as i32 + EXTRA_LINES - 1,
+            utils::HELP_COLOR,
+            footer,
+        );
+    }
+
+    // Esc, Enter or one of the `close` keys close the browser
+    pub fn run(&mut self, close: &[i32]) {
+        let p: PANEL = new_panel(self.win.w);
+        self.draw();
+        show_panel(p);
+        loop {
+            let ch = getch();
+            let page: isize = self.page() as isize;
+            match ch {
+                KEY_RESIZE => {
+                    utils::set_resized();
+                    clear();
+                    refresh();
+                    delwin(self.win.w);
+                    self.win = Browser::create_win();
+                    replace_panel(p, self.win.w);
+                    self.scroll(0);
+                }
+                KEY_UP => self.scroll(-1),
+                KEY_DOWN => self.scroll(1),
+                KEY_PPAGE => self.scroll(-page),
+                KEY_NPAGE => self.scroll(page),
+                KEY_HOME => self.top = 0,
+                KEY_END => self.scroll(isize::MAX / 2),
+                KEY_BACKSPACE | 127 | 8 => {
+                    self.prefix.pop();
+                    self.top = 0;
+                }
+                27 | 10 | KEY_ENTER => break,
+                _ if close.contains(&ch) => break,
+                _ if ch < 256 && (ch as u8).is_ascii_alphabetic() => {
+                    self.prefix.push((ch as u8).to_ascii_uppercase() as char);
+                    self.top = 0;
+                }
+                _ => continue,
+            }
+            self.draw();
+        }
+        hide_panel(p);
+        update_panels();
+        del_panel(p);
+        delwin(self.win.w);
+    }
+}
+
+pub fn show(words: &[String], close: &[i32]) {
+    let mut browser: Browser = Browser::new(words);
+    browser.run(close);
+}
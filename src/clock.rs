@@ -0,0 +1,157 @@
+/*
+ * Clock
+ * -----
+ * Playing time shown on the empty line between the keyboard and the help,
+ * it counts down when there is a time limit
+ */
+
+extern crate ncurses;
+
+use crate::utils;
+use ncurses::*;
+use std::time::{Duration, Instant};
+
+// How often the main loop wakes up to update the clock
+pub const TICK_MS: i32 = 250;
+const WIDTH: i32 = 32;
+
+pub struct Clock {
+    start: Instant,
+    // When the clock was stopped, e.g. for a dialog between puzzles
+    paused: Option<Instant>,
+    limit: Option<Duration>,
+    // Shown before the time, e.g. the puzzle number of a speedrun
+    label: String,
+    // None if the clock is not displayed
+    win: Option<WINDOW>,
+}
+
+impl Clock {
+    // Displayed clock just above the `y` line
+    pub fn new(limit: Option<Duration>, shown: bool, y: i32) -> Self {
+        Clock {
+            start: Instant::now(),
+            paused: None,
+            limit,
+            label: String::new(),
+            win: if shown {
+                Some(Clock::create_win(y))
+            } else {
+                None
+            },
+        }
+    }
+
+    fn create_win(y: i32) -> WINDOW {
+        let width: i32 = WIDTH.min(COLS());
+        newwin(1, width, y.max(0), ((COLS() - width) / 2).max(0))
+    }
+
+    // Start counting again for the next game
+    pub fn restart(&mut self) {
+        self.start = Instant::now();
+        self.paused = None;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused.get_or_insert_with(Instant::now);
+    }
+
+    // Go on without counting the time of the pause
+    pub fn resume(&mut self) {
+        if let Some(paused) = self.paused.take() {
+            self.start += paused.elapsed();
+        }
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.win.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.paused
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.start)
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.elapsed() >= limit)
+    }
+
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
+    // Recreate the window for the resized terminal
+    pub fn relocate(&mut self, y: i32) {
+        if let Some(win) = self.win {
+            delwin(win);
+            self.win = Some(Clock::create_win(y));
+        }
+    }
+
+    pub fn redraw(&self) {
+        let win: WINDOW = match self.win {
+            Some(w) => w,
+            None => return,
+        };
+        let time: String = match self.limit {
+            // Rounded up, the game is over at 0:00
+            Some(limit) => {
+                let left: Duration = limit.saturating_sub(self.elapsed());
+                format!(
+                    "Left {}",
+                    format(Duration::from_secs(left.as_secs_f64().ceil() as u64))
+                )
+            }
+            None => format!("Time {}", format(self.elapsed())),
+        };
+        let text: String = if self.label.is_empty() {
+            time
+        } else {
+            format!("{}   {}", self.label, time)
+        };
+        werase(win);
+        wattrset(win, utils::color(utils::HELP_COLOR));
+        mvwprintw(
+            win,
+            0,
+            ((getmaxx(win) - text.len() as i32) / 2).max(0),
+            &text,
+        );
+        wrefresh(win);
+    }
+}
+
+// Time as m:ss, or h:mm:ss from an hour
+pub fn format(d: Duration) -> String {
+    let secs: u64 = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_time() {
+        assert_eq!(format(Duration::from_millis(999)), "0:00");
+        assert_eq!(format(Duration::from_secs(83)), "1:23");
+        assert_eq!(format(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn pause_stops_counting() {
+        let mut clock = Clock::new(None, false, 0);
+        clock.pause();
+        let time: Duration = clock.elapsed();
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(clock.elapsed(), time);
+        clock.resume();
+        assert!(clock.elapsed() < Duration::from_millis(20));
+    }
+}
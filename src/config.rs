@@ -0,0 +1,437 @@
+/*
+ * Configuration
+ * -------------
+ * Defaults in $XDG_CONFIG_HOME/wordle-ncurses/config.toml, options given on
+ * the command line override them. Top level keys are the long option names:
+ *   length = 6
+ *   hard = true
+ *   dict = "/usr/share/dict/words"
+ * color pairs as foreground and background colors, either may be omitted,
+ * over the colors of the theme:
+ *   theme = "dark"
+ *   [colors]
+ *   in-place = { fg = "black", bg = "#85c0f9", bold = true }
+ * own themes based on the classic one with the same keys as [colors]:
+ *   [themes.mine]
+ *   screen = { bg = 235 }
+ * and function keys, Tab, Esc or a character other than a letter bound to
+ * the actions:
+ *   [keys]
+ *   hint = "?"
+ *   exit = "Esc"
+ */
+
+extern crate ncurses;
+
+use crate::error::{self, Error};
+use crate::paths;
+use crate::theme::{self, Color, Theme};
+use crate::utils;
+use ncurses::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    verbose: Option<bool>,
+    debug: Option<bool>,
+    length: Option<i32>,
+    tries: Option<i32>,
+    hard: Option<bool>,
+    daily: Option<bool>,
+    seed: Option<u64>,
+    secret: Option<String>,
+    dict: Option<String>,
+    answers: Option<String>,
+    absurd: Option<bool>,
+    boards: Option<i32>,
+    timed: Option<i32>,
+    speedrun: Option<i32>,
+    new: Option<bool>,
+    share_out: Option<String>,
+    share_copy: Option<bool>,
+    plain: Option<bool>,
+    markers: Option<bool>,
+    no_assist: Option<bool>,
+    json: Option<bool>,
+    theme: Option<String>,
+    colorblind: Option<bool>,
+    #[serde(default)]
+    colors: Colors,
+    #[serde(default)]
+    themes: BTreeMap<String, Colors>,
+    #[serde(default)]
+    pub keys: Keys,
+    // File the settings come from, for error messages
+    #[serde(skip)]
+    pub path: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Colors {
+    screen: Option<Pair>,
+    focus: Option<Pair>,
+    no_focus: Option<Pair>,
+    title: Option<Pair>,
+    unknown: Option<Pair>,
+    in_place: Option<Pair>,
+    not_in_place: Option<Pair>,
+    not_in_word: Option<Pair>,
+    norm: Option<Pair>,
+    error: Option<Pair>,
+    help: Option<Pair>,
+    selected: Option<Pair>,
+    not_selected: Option<Pair>,
+    debug: Option<Pair>,
+    key_unknown: Option<Pair>,
+    bar: Option<Pair>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pair {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: Option<bool>,
+}
+
+impl Colors {
+    // Replace the colors of the theme with the given ones
+    fn apply(&self, theme: &mut Theme) {
+        for (pair, colors) in [
+            (0, self.screen),
+            (utils::FOCUS_COLOR, self.focus),
+            (utils::NO_FOCUS_COLOR, self.no_focus),
+            (utils::TITLE_COLOR, self.title),
+            (utils::UNKNOWN_COLOR, self.unknown),
+            (utils::IN_PLACE_COLOR, self.in_place),
+            (utils::NOT_IN_PLACE_COLOR, self.not_in_place),
+            (utils::NOT_IN_WORD_COLOR, self.not_in_word),
+            (utils::NORM_COLOR, self.norm),
+            (utils::ERR_COLOR, self.error),
+            (utils::HELP_COLOR, self.help),
+            (utils::YESNO_SEL_COLOR, self.selected),
+            (utils::YESNO_NSEL_COLOR, self.not_selected),
+            (utils::DEBUG_COLOR, self.debug),
+            (utils::KEY_UNKNOWN_COLOR, self.key_unknown),
+            (utils::BAR_COLOR, self.bar),
+        ] {
+            if let Some(p) = colors {
+                let style: &mut theme::Style = &mut theme[pair as usize];
+                style.fg = p.fg.unwrap_or(style.fg);
+                style.bg = p.bg.unwrap_or(style.bg);
+                style.bold = p.bold.unwrap_or(style.bold);
+            }
+        }
+    }
+}
+
+// Key code as returned by getch()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Key(pub i32);
+
+const KEY_TAB: i32 = 9;
+const KEY_ESC: i32 = 27;
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        let function: Option<u8> = name
+            .strip_prefix(['F', 'f'])
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (1..=12).contains(n));
+        let mut chars = name.chars();
+        let c: Option<char> = chars.next().filter(|_| chars.next().is_none());
+        match (function, c) {
+            (Some(n), _) => Ok(Key(KEY_F(n))),
+            _ if name.eq_ignore_ascii_case("tab") => Ok(Key(KEY_TAB)),
+            _ if name.eq_ignore_ascii_case("esc") => Ok(Key(KEY_ESC)),
+            (_, Some(c)) if c.is_ascii_graphic() && !c.is_ascii_alphabetic() => Ok(Key(c as i32)),
+            _ => Err(format!(
+                "unknown key \"{}\", expected F1 to F12, Tab, Esc or a character other than a letter",
+                name
+            )),
+        }
+    }
+}
+
+impl Key {
+    pub fn name(self) -> String {
+        match self.0 {
+            KEY_TAB => "Tab".to_string(),
+            KEY_ESC => "Esc".to_string(),
+            c if c < 256 => (c as u8 as char).to_string(),
+            c => format!("F{}", c - KEY_F0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Keys {
+    pub help: Key,
+    pub hint: Key,
+    pub stats: Key,
+    pub words: Key,
+    pub exit: Key,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Keys {
+            help: Key(KEY_F(1)),
+            hint: Key(KEY_F(2)),
+            stats: Key(KEY_F(3)),
+            words: Key(KEY_F(4)),
+            exit: Key(KEY_F(10)),
+        }
+    }
+}
+
+impl Keys {
+    fn actions(&self) -> [(&'static str, Key); 5] {
+        [
+            ("help", self.help),
+            ("hint", self.hint),
+            ("stats", self.stats),
+            ("words", self.words),
+            ("exit", self.exit),
+        ]
+    }
+}
+
+impl Config {
+    // Missing file gives no defaults
+    pub fn load() -> Result<Self, Error> {
+        let path = match paths::config_file("config.toml") {
+            Some(p) => p,
+            None => return Ok(Config::default()),
+        };
+        let path_text: String = path.display().to_string();
+        let text: String = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(Error::ReadFile(path_text, e)),
+        };
+        let mut config: Config =
+            Config::parse(&text).map_err(|e| Error::Config(path_text.clone(), e))?;
+        config.path = path_text;
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config: Config = toml::from_str(text).map_err(|e| e.to_string())?;
+        for (key, value, max) in [
+            ("length", config.length, error::MAX_WORD_LEN),
+            ("tries", config.tries, error::MAX_TRIES),
+            ("boards", config.boards, error::MAX_BOARDS),
+            ("speedrun", config.speedrun, error::MAX_PUZZLES),
+            ("timed", config.timed, error::MAX_TIME_LIMIT),
+        ] {
+            if let Some(n) = value.filter(|n| !(1..=max).contains(n)) {
+                return Err(format!(
+                    "{} is out of range, expected 1 to {} for key `{}`",
+                    n, max, key
+                ));
+            }
+        }
+        for name in config.themes.keys() {
+            if theme::builtin(name).is_some() {
+                return Err(format!(
+                    "theme \"{}\" is built in, choose another name for key `themes.{}`",
+                    name, name
+                ));
+            }
+        }
+        if let Some(name) = &config.theme {
+            if !config.theme_names().contains(name) {
+                return Err(format!(
+                    "unknown theme \"{}\", expected one of {} for key `theme`",
+                    name,
+                    config.theme_names().join(", ")
+                ));
+            }
+        }
+        let actions = config.keys.actions();
+        for (idx, (action, key)) in actions.iter().enumerate() {
+            if let Some((other, _)) = actions[..idx].iter().find(|(_, k)| k == key) {
+                return Err(format!(
+                    "{} is already bound to {} for key `keys.{}`",
+                    key.name(),
+                    other,
+                    action
+                ));
+            }
+        }
+        Ok(config)
+    }
+
+    // Command line arguments the defaults stand for, with their keys, in
+    // the order of the options as a flag may require an earlier one
+    pub fn args(&self) -> Vec<(&'static str, String)> {
+        // None is a flag which is off, Some(None) one which is on
+        fn flag(value: Option<bool>) -> Option<Option<String>> {
+            value.filter(|&on| on).map(|_| None)
+        }
+        fn text<T: ToString>(value: &Option<T>) -> Option<Option<String>> {
+            value.as_ref().map(|v| Some(v.to_string()))
+        }
+        let options: [(&'static str, Option<Option<String>>); 23] = [
+            ("verbose", flag(self.verbose)),
+            ("debug", flag(self.debug)),
+            ("length", text(&self.length)),
+            ("tries", text(&self.tries)),
+            ("hard", flag(self.hard)),
+            ("daily", flag(self.daily)),
+            ("seed", text(&self.seed)),
+            ("secret", text(&self.secret)),
+            ("dict", text(&self.dict)),
+            ("answers", text(&self.answers)),
+            ("absurd", flag(self.absurd)),
+            ("boards", text(&self.boards)),
+            ("timed", text(&self.timed)),
+            ("speedrun", text(&self.speedrun)),
+            ("new", flag(self.new)),
+            ("share-out", text(&self.share_out)),
+            ("share-copy", flag(self.share_copy)),
+            ("plain", flag(self.plain)),
+            ("markers", flag(self.markers)),
+            ("no-assist", flag(self.no_assist)),
+            ("json", flag(self.json)),
+            ("theme", text(&self.theme)),
+            ("colorblind", flag(self.colorblind)),
+        ];
+        options
+            .into_iter()
+            .filter_map(|(key, value)| {
+                value.map(|v| match v {
+                    Some(v) => (key, format!("--{}={}", key, v)),
+                    None => (key, format!("--{}", key)),
+                })
+            })
+            .collect()
+    }
+
+    // Built-in themes and the ones from the config file
+    pub fn theme_names(&self) -> Vec<String> {
+        theme::BUILTIN
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(self.themes.keys().cloned())
+            .collect()
+    }
+
+    // Theme of the name, the classic one by default, with the colorblind
+    // colors and the [colors] table applied over it
+    pub fn theme(&self, name: Option<&str>, colorblind: bool) -> Result<Theme, Error> {
+        let mut theme: Theme = theme::CLASSIC;
+        if let Some(name) = name {
+            match (theme::builtin(name), self.themes.get(name)) {
+                (Some(t), _) => theme = t,
+                (None, Some(colors)) => colors.apply(&mut theme),
+                (None, None) => {
+                    return Err(Error::UnknownTheme(name.to_string(), self.theme_names()))
+                }
+            }
+        }
+        if colorblind {
+            theme::colorblind(&mut theme);
+        }
+        self.colors.apply(&mut theme);
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_bindings() {
+        let config = Config::parse(
+            "length = 6\nhard = true\nplain = false\nmarkers = true\nshare-out = \"a b\"\n\
+             [colors]\nin-place = { bg = \"Blue\" }\n[keys]\nhint = \"?\"\nexit = \"esc\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.args(),
+            vec![
+                ("length", "--length=6".to_string()),
+                ("hard", "--hard".to_string()),
+                ("share-out", "--share-out=a b".to_string()),
+                ("markers", "--markers".to_string()),
+            ]
+        );
+        let theme: Theme = config.theme(None, false).unwrap();
+        assert_eq!(
+            theme[utils::IN_PLACE_COLOR as usize].bg,
+            Color::Basic(COLOR_BLUE)
+        );
+        assert_eq!(theme[utils::FOCUS_COLOR as usize], theme::CLASSIC[1]);
+        assert_eq!(
+            (config.keys.hint.name(), config.keys.exit.name()),
+            ("?".to_string(), "Esc".to_string())
+        );
+        assert_eq!(config.keys.help, Key(KEY_F(1)));
+        assert!(Config::parse("").unwrap().args().is_empty());
+    }
+
+    #[test]
+    fn themes() {
+        let config = Config::parse(
+            "theme = \"mine\"\n[themes.mine]\nscreen = { bg = 235 }\n\
+             [colors.in-place]\nbg = \"#85c0f9\"\nbold = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.args(), vec![("theme", "--theme=mine".to_string())]);
+        let mine: Theme = config.theme(Some("mine"), false).unwrap();
+        assert_eq!(mine[0].bg, Color::Indexed(235));
+        assert_eq!(
+            mine[utils::IN_PLACE_COLOR as usize].bg,
+            Color::Rgb(0x85, 0xc0, 0xf9)
+        );
+        assert!(mine[utils::IN_PLACE_COLOR as usize].bold);
+        let dark: Theme = config.theme(Some("dark"), true).unwrap();
+        assert_eq!(dark[0], theme::builtin("dark").unwrap()[0]);
+        assert_eq!(
+            dark[utils::NOT_IN_PLACE_COLOR as usize].bg,
+            Color::Rgb(0x3b, 0x7d, 0xdd)
+        );
+        assert_eq!(
+            dark[utils::IN_PLACE_COLOR as usize].bg,
+            Color::Rgb(0x85, 0xc0, 0xf9)
+        );
+        assert_eq!(
+            config.theme(Some("sepia"), false).unwrap_err().to_string(),
+            "Unknown theme \"sepia\", it should be one of classic, dark, light, high-contrast, mine"
+        );
+    }
+
+    #[test]
+    fn errors_name_the_key() {
+        let error = |text: &str| Config::parse(text).unwrap_err();
+        assert!(error("lenght = 6").contains("unknown field `lenght`"));
+        assert!(error("length = \"6\"").contains("for key `length`"));
+        assert!(error("tries = 0").contains("for key `tries`"));
+        assert!(error("[colors.in-place]\nfg = \"gren\"").contains(
+            "unknown color \"gren\", expected one of black, red, green, yellow, blue, magenta, \
+             cyan, white, default, #rrggbb or 0 to 255 for key `colors.in-place.fg`"
+        ));
+        assert!(error("[colors.bar]\nbg = 300").contains("for key `colors.bar.bg`"));
+        assert!(error("[themes.dark.bar]\nbg = 1").contains("for key `themes.dark`"));
+        assert!(error("theme = \"mine\"").contains("for key `theme`"));
+        assert!(error("[keys]\nhelp = \"h\"").contains("for key `keys.help`"));
+        assert!(error("[keys]\nhelp = \"F13\"").contains("for key `keys.help`"));
+        assert_eq!(
+            error("[keys]\nstats = \"F1\""),
+            "F1 is already bound to help for key `keys.stats`"
+        );
+    }
+}
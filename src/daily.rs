@@ -0,0 +1,72 @@
+/*
+ * Daily puzzle
+ * ------------
+ * Deterministic word selection derived from the local date.
+ */
+
+use chrono::{Local, NaiveDate};
+
+// Day of the original wordle puzzle #0
+const FIRST_DAY: (i32, u32, u32) = (2021, 6, 19);
+
+pub fn puzzle_number(date: NaiveDate) -> i64 {
+    let first = NaiveDate::from_ymd_opt(FIRST_DAY.0, FIRST_DAY.1, FIRST_DAY.2).unwrap();
+    (date - first).num_days()
+}
+
+pub fn today() -> i64 {
+    puzzle_number(Local::now().date_naive())
+}
+
+// splitmix64 finalizer, stable across platforms and library versions
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// Index of the puzzle word in a list of `count` words of length `wlen`
+pub fn word_index(puzzle: i64, wlen: i32, count: usize) -> usize {
+    board_index(puzzle, wlen, 0, count)
+}
+
+// Same as word_index() for the given board of a multi-board puzzle, the
+// first board has the usual word
+pub fn board_index(puzzle: i64, wlen: i32, board: usize, count: usize) -> usize {
+    (mix((puzzle as u64) << 8 ^ wlen as u64 ^ (board as u64) << 48) % count as u64) as usize
+}
+
+// Index of the word chosen by a --seed for the given board, reproducible
+// on every platform unlike a seeded random generator
+pub fn seed_index(seed: u64, wlen: i32, board: usize, count: usize) -> usize {
+    (mix(mix(seed) ^ wlen as u64 ^ (board as u64) << 48) % count as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle_numbers() {
+        assert_eq!(
+            puzzle_number(NaiveDate::from_ymd_opt(2021, 6, 19).unwrap()),
+            0
+        );
+        assert_eq!(
+            puzzle_number(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()),
+            196
+        );
+    }
+
+    #[test]
+    fn word_index_is_stable() {
+        assert_eq!(word_index(500, 5, 1000), 415);
+        assert_eq!(word_index(500, 6, 1000), 545);
+        assert_eq!(word_index(7, 5, 1), 0);
+        assert_eq!(board_index(500, 5, 0, 1000), 415);
+        assert_ne!(board_index(500, 5, 1, 1000), 415);
+        assert_eq!(seed_index(7, 5, 0, 1000), 830);
+        assert_eq!(seed_index(7, 5, 1, 1000), 573);
+    }
+}
@@ -0,0 +1,18 @@
+/*
+ * Word lists
+ * ----------
+ */
+
+use crate::error::Error;
+use std::fs;
+use wordle_engine::words;
+
+// Load a word list file, fails if there is no usable word of the requested length
+pub fn load_file(path: &str, word_len: i32) -> Result<Vec<String>, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::ReadFile(path.to_string(), e))?;
+    let words = words::parse(&text, word_len);
+    if words.is_empty() {
+        return Err(Error::NoWordsInFile(path.to_string(), word_len));
+    }
+    Ok(words)
+}
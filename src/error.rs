@@ -0,0 +1,119 @@
+/*
+ * Errors
+ * ------
+ */
+
+use std::fmt;
+use std::io;
+
+pub const MAX_WORD_LEN: i32 = 31;
+pub const MAX_TRIES: i32 = 30;
+pub const MAX_BOARDS: i32 = 16;
+pub const MAX_PUZZLES: i32 = 100;
+pub const MAX_TIME_LIMIT: i32 = 86400;
+
+#[derive(Debug)]
+pub enum Error {
+    // Word length out of the supported range
+    WordLength(i32),
+    // Amount of attempts out of the supported range
+    Tries(i32),
+    // Amount of boards out of the supported range
+    Boards(i32),
+    // Amount of speedrun puzzles out of the supported range
+    Puzzles(i32),
+    // Time limit in seconds out of the supported range
+    TimeLimit(i32),
+    // Fewer words to choose from than secret words needed for all boards
+    // and puzzles: words, word length, secret words
+    NotEnoughWords(usize, i32, i32),
+    // No words of the requested length in the built-in dictionary
+    NoWords(i32),
+    // Invalid config file: path, reason naming the key
+    Config(String, String),
+    // Theme which is neither built in nor in the config file: name, known themes
+    UnknownTheme(String, Vec<String>),
+    // Word list file can't be read
+    ReadFile(String, io::Error),
+    // No words of the requested length in a word list file
+    NoWordsInFile(String, i32),
+    // Secret word given on the command line has a wrong length
+    GivenWordLength(String, i32),
+    // Secret word given on the command line is not in a dictionary
+    UnknownWord(String),
+    // Secret word choice for an absurd game, which has no secret word
+    AbsurdSecret,
+    // Dictionary preparation thread terminated without a secret word
+    Prepare,
+    // Standard input or output failed in the plain text or JSON mode
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::WordLength(n) => write!(
+                f,
+                "Word length {} is not supported, it should be from 1 to {}",
+                n, MAX_WORD_LEN
+            ),
+            Error::Tries(n) => write!(
+                f,
+                "Amount of attempts {} is not supported, it should be from 1 to {}",
+                n, MAX_TRIES
+            ),
+            Error::Boards(n) => write!(
+                f,
+                "Amount of boards {} is not supported, it should be from 1 to {}",
+                n, MAX_BOARDS
+            ),
+            Error::Puzzles(n) => write!(
+                f,
+                "Amount of puzzles {} is not supported, it should be from 1 to {}",
+                n, MAX_PUZZLES
+            ),
+            Error::TimeLimit(n) => write!(
+                f,
+                "Time limit {} seconds is not supported, it should be from 1 to {}",
+                n, MAX_TIME_LIMIT
+            ),
+            Error::NotEnoughWords(count, wlen, needed) => write!(
+                f,
+                "Only {} {}-letter words to choose from, {} different ones are needed",
+                count, wlen, needed
+            ),
+            Error::NoWords(n) => write!(
+                f,
+                "No {}-letter words in the dictionary, try another word length",
+                n
+            ),
+            Error::Config(path, reason) => write!(f, "Invalid config \"{}\": {}", path, reason),
+            Error::UnknownTheme(name, names) => write!(
+                f,
+                "Unknown theme \"{}\", it should be one of {}",
+                name,
+                names.join(", ")
+            ),
+            Error::ReadFile(path, e) => write!(f, "Can't read \"{}\": {}", path, e),
+            Error::NoWordsInFile(path, n) => write!(
+                f,
+                "No {}-letter words in \"{}\", try another word length",
+                n, path
+            ),
+            Error::GivenWordLength(w, n) => write!(
+                f,
+                "Word \"{}\" should be {} letters long, use -w to change the word length",
+                w, n
+            ),
+            Error::UnknownWord(w) => write!(f, "Word \"{}\" is not in a dictionary", w),
+            Error::AbsurdSecret => write!(
+                f,
+                "There is no secret word to choose in the absurd mode, drop word, daily and seed"
+            ),
+            Error::Prepare => write!(f, "Dictionary preparation failed"),
+            Error::Io(e) => write!(f, "Input/output error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
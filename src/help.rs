@@ -4,6 +4,8 @@
  */
 extern crate ncurses;
 
+use crate::config::Keys;
+use crate::lb::{self, Role};
 use crate::utils;
 use ncurses::*;
 
@@ -23,7 +25,7 @@ impl Help {
         let help: Vec<String> = help_str.lines().map(|x| x.to_string()).collect();
         let legend_lines = if self.print_legend { 3 } else { 1 };
 
-        wattrset(self.win, COLOR_PAIR(utils::HELP_COLOR));
+        wattrset(self.win, utils::color(utils::HELP_COLOR));
 
         // Print help
         let mut y: i32 = 0;
@@ -40,42 +42,53 @@ impl Help {
             struct Leg<'a> {
                 relative_line: i32,
                 color: i16,
+                attrs: attr_t,
                 legend: &'a str,
             }
+            let in_place: String = lb::sample(Role::InPlace);
+            let not_in_place: String = lb::sample(Role::NotInPlace);
+            let not_in_word: String = lb::sample(Role::NotInWord);
             let leg: Vec<Leg> = vec![
                 Leg {
                     relative_line: 0,
                     color: utils::HELP_COLOR,
+                    attrs: A_NORMAL(),
                     legend: "Legend: ",
                 },
                 Leg {
                     relative_line: 0,
                     color: utils::IN_PLACE_COLOR,
-                    legend: " X ",
+                    attrs: lb::marker_attrs(Role::InPlace),
+                    legend: &in_place,
                 },
                 Leg {
                     relative_line: 0,
                     color: utils::HELP_COLOR,
+                    attrs: A_NORMAL(),
                     legend: " - letter in a correct place",
                 },
                 Leg {
                     relative_line: 1,
                     color: utils::NOT_IN_PLACE_COLOR,
-                    legend: " X ",
+                    attrs: lb::marker_attrs(Role::NotInPlace),
+                    legend: &not_in_place,
                 },
                 Leg {
                     relative_line: 1,
                     color: utils::HELP_COLOR,
+                    attrs: A_NORMAL(),
                     legend: " - letter eixsts in the wrong place ",
                 },
                 Leg {
                     relative_line: 2,
                     color: utils::NOT_IN_WORD_COLOR,
-                    legend: " X ",
+                    attrs: lb::marker_attrs(Role::NotInWord),
+                    legend: &not_in_word,
                 },
                 Leg {
                     relative_line: 2,
                     color: utils::HELP_COLOR,
+                    attrs: A_NORMAL(),
                     legend: " - letter doesn't exist in the word ",
                 },
             ];
@@ -87,7 +100,7 @@ impl Help {
                     y += 1;
                     prev_line = l.relative_line;
                 }
-                wattrset(self.win, COLOR_PAIR(l.color));
+                wattrset(self.win, utils::color(l.color) | l.attrs);
                 mvwprintw(self.win, y, x, l.legend);
                 x += l.legend.len() as i32;
             }
@@ -105,7 +118,16 @@ impl Help {
                 width = m.len() as i32;
             }
         }
-        let win = newwin(
+        Help {
+            height,
+            width,
+            print_legend,
+            win: Help::create_win(height, width),
+        }
+    }
+
+    fn create_win(height: i32, width: i32) -> WINDOW {
+        newwin(
             height,
             width,
             LINES() - height,
@@ -114,15 +136,24 @@ impl Help {
             } else {
                 0
             },
-        );
-        Help {
-            height,
-            width,
-            print_legend,
-            win,
+        )
+    }
+
+    // Widen the window if a line of the text doesn't fit it
+    pub fn fit(&mut self, help_str: &str) {
+        let width: i32 = help_str.lines().map(|m| m.len() as i32).max().unwrap_or(0);
+        if width > self.width {
+            self.width = width;
+            self.relocate();
         }
     }
 
+    // Recreate the window at the bottom of the resized terminal
+    pub fn relocate(&mut self) {
+        delwin(self.win);
+        self.win = Help::create_win(self.height, self.width);
+    }
+
     pub fn refresh(&self) {
         wrefresh(self.win);
     }
@@ -132,22 +163,45 @@ impl Help {
  * DETAILED HELP BIG  WINDOW
  * --------------------------
  */
-enum HelpElement<'a> {
+pub enum HelpElement<'a> {
     Text(&'a str),
     Color(i16),
+    // Attributes added to the texts up to the next color
+    Attr(attr_t),
     Skip(i32),
     NewLine,
     SavePosition(usize),
     RestorePosition(usize),
 }
 
-pub fn detailed_help(debug: bool, secret_word: &str) {
-    let title = " Help ";
+pub fn detailed_help(debug: bool, assist: bool, secret_words: &[&str], keys: &Keys) {
+    let key_lines: Vec<String> = [
+        (keys.help, "Display this help screen"),
+        (keys.hint, "Suggest the next guess"),
+        (keys.stats, "Display game statistics"),
+        (keys.words, "Browse words matching the hints"),
+        (keys.exit, "Exit"),
+    ]
+    .iter()
+    .map(|(key, text)| format!("{:<10}- {}", key.name(), text))
+    .collect();
+    let in_place: String = lb::sample(Role::InPlace);
+    let not_in_place: String = lb::sample(Role::NotInPlace);
+    let not_in_word: String = lb::sample(Role::NotInWord);
     let mut help_elements: Vec<HelpElement> = vec![
         HelpElement::Color(utils::NORM_COLOR),
-        HelpElement::Text("F1        - Display this help screen"),
+        HelpElement::Text(&key_lines[0]),
         HelpElement::NewLine,
-        HelpElement::Text("F10       - Exit"),
+    ];
+    if assist {
+        help_elements.extend([HelpElement::Text(&key_lines[1]), HelpElement::NewLine]);
+    }
+    help_elements.extend([HelpElement::Text(&key_lines[2]), HelpElement::NewLine]);
+    if assist {
+        help_elements.extend([HelpElement::Text(&key_lines[3]), HelpElement::NewLine]);
+    }
+    help_elements.extend([
+        HelpElement::Text(&key_lines[4]),
         HelpElement::NewLine,
         HelpElement::Text("Enter     - "),
         HelpElement::SavePosition(0),
@@ -192,31 +246,37 @@ pub fn detailed_help(debug: bool, secret_word: &str) {
         HelpElement::NewLine,
         HelpElement::RestorePosition(0),
         HelpElement::Color(utils::IN_PLACE_COLOR),
-        HelpElement::Text(" X "),
+        HelpElement::Attr(lb::marker_attrs(Role::InPlace)),
+        HelpElement::Text(&in_place),
         HelpElement::Color(utils::NORM_COLOR),
         HelpElement::Text(" - Letter exists in the word and located in a correct place."),
         HelpElement::NewLine,
         HelpElement::RestorePosition(0),
         HelpElement::Color(utils::NOT_IN_PLACE_COLOR),
-        HelpElement::Text(" X "),
+        HelpElement::Attr(lb::marker_attrs(Role::NotInPlace)),
+        HelpElement::Text(&not_in_place),
         HelpElement::Color(utils::NORM_COLOR),
         HelpElement::Text(" - Letter exists in the word but located in a wrong place."),
         HelpElement::NewLine,
         HelpElement::RestorePosition(0),
         HelpElement::Color(utils::NOT_IN_WORD_COLOR),
-        HelpElement::Text(" X "),
+        HelpElement::Attr(lb::marker_attrs(Role::NotInWord)),
+        HelpElement::Text(&not_in_word),
         HelpElement::Color(utils::NORM_COLOR),
         HelpElement::Text(" - Letter doesn't exist in the word"),
-    ];
+    ]);
+    let quoted: Vec<String> = secret_words.iter().map(|w| format!("\"{}\"", w)).collect();
+    let secret_text: String = quoted.join(", ");
     if debug {
         help_elements.push(HelpElement::NewLine);
         help_elements.push(HelpElement::NewLine);
         help_elements.push(HelpElement::Color(utils::HELP_COLOR));
-        help_elements.push(HelpElement::Text(
-            "       DEBUG MODE: The secret word is \"",
-        ));
-        help_elements.push(HelpElement::Text(secret_word));
-        help_elements.push(HelpElement::Text("\""));
+        help_elements.push(HelpElement::Text(if secret_words.len() > 1 {
+            "       DEBUG MODE: The secret words are "
+        } else {
+            "       DEBUG MODE: The secret word is "
+        }));
+        help_elements.push(HelpElement::Text(&secret_text));
     }
     let help_elements_rest: Vec<HelpElement> = vec![
         HelpElement::NewLine,
@@ -229,31 +289,30 @@ pub fn detailed_help(debug: bool, secret_word: &str) {
     for e in help_elements_rest {
         help_elements.push(e);
     }
+    show_elements(" Help ", &help_elements);
+}
 
+// Display a window with the elements and wait for any key
+pub fn show_elements(title: &str, help_elements: &[HelpElement]) {
     // Caclulate width and height
     let mut height: i32 = 0;
     let mut width: i32 = 0;
     let mut position: i32 = 0;
     let mut max_positions: usize = 0;
-    for e in &help_elements {
+    for e in help_elements {
         match e {
-            HelpElement::SavePosition(n) => {
-                if max_positions < *n {
-                    max_positions = *n;
-                }
-            }
-            HelpElement::RestorePosition(n) => {
-                if max_positions < *n {
-                    max_positions = *n;
-                }
+            HelpElement::SavePosition(n) | HelpElement::RestorePosition(n)
+                if max_positions < *n =>
+            {
+                max_positions = *n;
             }
             _ => {}
         }
     }
-    let mut old_positions: Vec<usize> = vec![0, max_positions];
-    for e in &help_elements {
+    let mut old_positions: Vec<usize> = vec![0; max_positions + 1];
+    for e in help_elements {
         match e {
-            HelpElement::Color(c) => {}
+            HelpElement::Color(_) | HelpElement::Attr(_) => {}
             HelpElement::Skip(n) => {
                 position += n;
             }
@@ -279,21 +338,51 @@ pub fn detailed_help(debug: bool, secret_word: &str) {
     // window placement and creation
     width += 2 + utils::LEFT_BW + utils::LEFT_BW;
     height += 2 + utils::TOP_BW + utils::BOT_BW;
+    let mut win: WINDOW = draw_elements(title, help_elements, height, width, max_positions);
+
+    let p: PANEL = new_panel(win);
+    show_panel(p);
+    while getch() == KEY_RESIZE {
+        utils::set_resized();
+        clear();
+        refresh();
+        delwin(win);
+        win = draw_elements(title, help_elements, height, width, max_positions);
+        replace_panel(p, win);
+    }
+    hide_panel(p);
+    update_panels();
+    del_panel(p);
+    delwin(win);
+}
+
+// Create a window in the middle of the screen and print the elements there
+fn draw_elements(
+    title: &str,
+    help_elements: &[HelpElement],
+    height: i32,
+    width: i32,
+    max_positions: usize,
+) -> WINDOW {
+    let mut old_positions: Vec<usize> = vec![0; max_positions + 1];
     let x: i32 = (COLS() - utils::LEFT_BW - utils::RIGHT_BW - width) / 2 + utils::LEFT_BW;
     let y: i32 = (LINES() - utils::TOP_BW - utils::BOT_BW - height) / 2 + utils::TOP_BW;
     let win: WINDOW = newwin(height, width, y, x);
-    wattrset(win, COLOR_PAIR(utils::NO_FOCUS_COLOR));
+    wattrset(win, utils::color(utils::NO_FOCUS_COLOR));
     box_(win, 0, 0);
-    wattrset(win, COLOR_PAIR(utils::TITLE_COLOR));
+    wattrset(win, utils::color(utils::TITLE_COLOR));
     mvwprintw(win, 0, (width - title.len() as i32) / 2, title);
 
     // print help content
     let mut y = utils::TOP_BW + 1;
     let mut position = utils::LEFT_BW + 1;
-    for e in &help_elements {
+    for e in help_elements {
         match e {
             HelpElement::Color(c) => {
-                wattrset(win, COLOR_PAIR(*c));
+                wattrset(win, utils::color(*c));
+            }
+            HelpElement::Attr(a) => {
+                wattron(win, *a);
             }
             HelpElement::Skip(n) => {
                 position += n;
@@ -316,11 +405,5 @@ pub fn detailed_help(debug: bool, secret_word: &str) {
     }
     wmove(win, utils::TOP_BW, utils::LEFT_BW);
     wrefresh(win);
-
-    let p: PANEL = new_panel(win);
-    show_panel(p);
-    getch();
-    hide_panel(p);
-    update_panels();
-    del_panel(p);
+    win
 }
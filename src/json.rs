@@ -0,0 +1,271 @@
+/*
+ * JSON lines protocol
+ * -------------------
+ * One command per input line:
+ *   {"guess":"CRANE"}
+ *   {"new_game":{"length":5,"tries":6,"hard":false,"daily":false,"seed":7,"word":"CRANE"}}
+ * where every "new_game" setting is optional. One event per output line:
+ *   {"event":"started","length":5,"tries":6,"hard":false,"game_id":""}
+ *   {"event":"feedback","guess":"SLATE","roles":["absent","absent","correct","absent","correct"],"attempt":1}
+ *   {"event":"invalid_word","guess":"XXXXX","reason":"Not in a dictionary"}
+ *   {"event":"won","attempt":2,"share":"..."}
+ *   {"event":"lost","secret":"CRANE","share":"..."}
+ *   {"event":"error","message":"..."}
+ */
+
+use crate::share;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+use wordle_engine::{Game, Role, State};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewGame {
+    pub length: Option<i32>,
+    pub tries: Option<i32>,
+    pub hard: Option<bool>,
+    pub daily: Option<bool>,
+    pub seed: Option<u64>,
+    pub word: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Command {
+    Guess(String),
+    NewGame(NewGame),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Started {
+        length: usize,
+        tries: usize,
+        hard: bool,
+        game_id: &'a str,
+    },
+    Feedback {
+        guess: &'a str,
+        roles: Vec<&'static str>,
+        attempt: usize,
+    },
+    InvalidWord {
+        guess: &'a str,
+        reason: String,
+    },
+    Won {
+        attempt: usize,
+        share: String,
+    },
+    Lost {
+        secret: &'a str,
+        share: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+// Started game with its dictionary, None accepts any word
+pub struct Setup {
+    pub game: Game,
+    pub words: Option<Rc<[String]>>,
+    pub puzzle: Option<i64>,
+    pub game_id: String,
+}
+
+fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::InPlace => "correct",
+        Role::NotInPlace => "present",
+        Role::NotInWord => "absent",
+        Role::UnknownYet => "unknown",
+    }
+}
+
+fn emit<W: Write>(out: &mut W, event: &Event) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, event)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+fn started<W: Write>(out: &mut W, setup: &Setup) -> io::Result<()> {
+    emit(
+        out,
+        &Event::Started {
+            length: setup.game.word_len(),
+            tries: setup.game.tries(),
+            hard: setup.game.rules().hard,
+            game_id: &setup.game_id,
+        },
+    )
+}
+
+fn share_text(setup: &Setup) -> String {
+    share::game_text(&setup.game, setup.puzzle)
+}
+
+fn guess<W: Write>(out: &mut W, setup: &mut Setup, word: &str) -> io::Result<()> {
+    if setup.game.state() != State::InProgress {
+        return emit(
+            out,
+            &Event::Error {
+                message: "The game is over, send new_game to start another one".to_string(),
+            },
+        );
+    }
+    let word: String = word.trim().to_uppercase();
+    let roles: Vec<Role> = match setup.game.guess(&word, setup.words.as_deref()) {
+        Ok(roles) => roles,
+        Err(e) => {
+            return emit(
+                out,
+                &Event::InvalidWord {
+                    guess: &word,
+                    reason: e.to_string(),
+                },
+            )
+        }
+    };
+    emit(
+        out,
+        &Event::Feedback {
+            guess: &word,
+            roles: roles.into_iter().map(role_name).collect(),
+            attempt: setup.game.guesses().len(),
+        },
+    )?;
+    match setup.game.state() {
+        State::Won(attempt) => emit(
+            out,
+            &Event::Won {
+                attempt,
+                share: share_text(setup),
+            },
+        ),
+        State::Lost => emit(
+            out,
+            &Event::Lost {
+                secret: setup.game.secret(),
+                share: share_text(setup),
+            },
+        ),
+        State::InProgress => Ok(()),
+    }
+}
+
+// Serve commands until the input ends. `new_game` starts a game with the
+// given settings or returns a reason it can't.
+pub fn serve<R, W, F>(input: R, out: &mut W, setup: Setup, mut new_game: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(NewGame) -> Result<Setup, String>,
+{
+    let mut setup: Setup = setup;
+    started(out, &setup)?;
+    for line in input.lines() {
+        let line: String = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Command>(&line) {
+            Ok(Command::Guess(word)) => guess(out, &mut setup, &word)?,
+            Ok(Command::NewGame(settings)) => match new_game(settings) {
+                Ok(s) => {
+                    setup = s;
+                    started(out, &setup)?;
+                }
+                Err(message) => emit(out, &Event::Error { message })?,
+            },
+            Err(e) => emit(
+                out,
+                &Event::Error {
+                    message: format!("Bad command: {}", e),
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordle_engine::Rules;
+
+    fn setup(secret: &str) -> Setup {
+        Setup {
+            game: Game::new(secret, 2, Rules::default()),
+            words: Some(vec!["CRANE".to_string(), "SLATE".to_string()].into()),
+            puzzle: None,
+            game_id: String::new(),
+        }
+    }
+
+    fn session(input: &str) -> Vec<String> {
+        let mut out: Vec<u8> = Vec::new();
+        serve(input.as_bytes(), &mut out, setup("CRANE"), |ng| {
+            match ng.word {
+                Some(w) => Ok(setup(&w)),
+                None => Err("no word".to_string()),
+            }
+        })
+        .unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| l.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            serde_json::from_str::<Command>(r#"{"guess":"crane"}"#).unwrap(),
+            Command::Guess("crane".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<Command>(r#"{"new_game":{"tries":3}}"#).unwrap(),
+            Command::NewGame(NewGame {
+                tries: Some(3),
+                ..NewGame::default()
+            })
+        );
+        assert!(serde_json::from_str::<Command>(r#"{"new_game":{"colour":1}}"#).is_err());
+    }
+
+    #[test]
+    fn game_events() {
+        let events = session(
+            "{\"guess\":\"xxxxx\"}\n\n{\"guess\":\"slate\"}\n{\"guess\":\"crane\"}\n\
+             {\"guess\":\"crane\"}\n",
+        );
+        assert_eq!(
+            events,
+            vec![
+                r#"{"event":"started","length":5,"tries":2,"hard":false,"game_id":""}"#,
+                r#"{"event":"invalid_word","guess":"XXXXX","reason":"Not in a dictionary"}"#,
+                r#"{"event":"feedback","guess":"SLATE","roles":["absent","absent","correct","absent","correct"],"attempt":1}"#,
+                r#"{"event":"feedback","guess":"CRANE","roles":["correct","correct","correct","correct","correct"],"attempt":2}"#,
+                r#"{"event":"won","attempt":2,"share":"Wordle-ncurses 2/2\n\n⬛⬛🟩⬛🟩\n🟩🟩🟩🟩🟩\n"}"#,
+                r#"{"event":"error","message":"The game is over, send new_game to start another one"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn new_games_and_errors() {
+        let events = session(
+            "{\"new_game\":{\"word\":\"SLATE\"}}\n{\"guess\":\"crane\"}\n{\"guess\":\"crane\"}\n\
+             {\"new_game\":{}}\nnonsense\n",
+        );
+        assert_eq!(events.len(), 7);
+        assert!(events[1].starts_with(r#"{"event":"started""#));
+        assert!(events[4].starts_with(r#"{"event":"lost","secret":"SLATE""#));
+        assert_eq!(events[5], r#"{"event":"error","message":"no word"}"#);
+        assert!(events[6].starts_with(r#"{"event":"error","message":"Bad command: "#));
+    }
+}
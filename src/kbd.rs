@@ -0,0 +1,122 @@
+/*
+ * Keyboard
+ * --------
+ * On-screen keyboard showing the best known state of every letter
+ */
+
+extern crate ncurses;
+
+use crate::lb::Role;
+use crate::utils;
+use ncurses::*;
+
+const ROWS: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+// Key cell widths in a preferred order: " Q " plus a gap, "Q" plus a gap
+const KEY_WIDTHS: [i32; 2] = [4, 2];
+
+pub struct Keyboard {
+    pub height: i32,
+    pub width: i32,
+    key_width: i32,
+    lines: Vec<String>,
+    roles: [Role; 26],
+    win: WINDOW,
+}
+
+impl Keyboard {
+    // Create the keyboard window with its bottom line just above `bottom`
+    pub fn new(bottom: i32) -> Self {
+        let mut key_width: i32 = KEY_WIDTHS[KEY_WIDTHS.len() - 1];
+        for w in KEY_WIDTHS {
+            if w * ROWS[0].len() as i32 - 1 <= COLS() {
+                key_width = w;
+                break;
+            }
+        }
+
+        // Wrap keyboard rows which don't fit the terminal width
+        let per_line: usize = std::cmp::max(1, (COLS() + 1) / key_width) as usize;
+        let mut lines: Vec<String> = Vec::new();
+        for row in ROWS {
+            let keys: Vec<char> = row.chars().collect();
+            for chunk in keys.chunks(per_line) {
+                lines.push(chunk.iter().collect());
+            }
+        }
+
+        let height: i32 = lines.len() as i32;
+        let width: i32 = lines.iter().map(|l| l.len()).max().unwrap_or(0) as i32 * key_width - 1;
+        let win = newwin(
+            height,
+            width,
+            std::cmp::max(bottom - height, 0),
+            if width < COLS() {
+                (COLS() - width) / 3
+            } else {
+                0
+            },
+        );
+        Keyboard {
+            height,
+            width,
+            key_width,
+            lines,
+            roles: [Role::UnknownYet; 26],
+            win,
+        }
+    }
+
+    // Recreate the keyboard for the resized terminal keeping the letter states
+    pub fn relocate(&mut self, bottom: i32) {
+        delwin(self.win);
+        let roles = self.roles;
+        *self = Keyboard::new(bottom);
+        self.roles = roles;
+    }
+
+    // Forget the letter states for the next game
+    pub fn reset(&mut self) {
+        self.roles = [Role::UnknownYet; 26];
+    }
+
+    // Remember the letter state if it is better than the known one
+    pub fn set(&mut self, c: char, role: Role) {
+        if c.is_ascii_alphabetic() {
+            let idx = (c.to_ascii_uppercase() as u8 - b'A') as usize;
+            self.roles[idx] = std::cmp::max(self.roles[idx], role);
+        }
+    }
+
+    pub fn get(&self, c: char) -> Role {
+        self.roles[(c.to_ascii_uppercase() as u8 - b'A') as usize]
+    }
+
+    pub fn redraw(&self) {
+        werase(self.win);
+        for (y, line) in self.lines.iter().enumerate() {
+            // Shift every next line a bit like on a real keyboard
+            let indent: i32 = (self.width - line.len() as i32 * self.key_width + 1) / 2;
+            for (n, c) in line.chars().enumerate() {
+                let color = match self.get(c) {
+                    Role::UnknownYet => utils::KEY_UNKNOWN_COLOR,
+                    Role::NotInWord => utils::NOT_IN_WORD_COLOR,
+                    Role::NotInPlace => utils::NOT_IN_PLACE_COLOR,
+                    Role::InPlace => utils::IN_PLACE_COLOR,
+                };
+                let key: String = if self.key_width > 2 {
+                    format!(" {} ", c)
+                } else {
+                    c.to_string()
+                };
+                wattrset(self.win, utils::color(color));
+                mvwprintw(self.win, y as i32, indent + n as i32 * self.key_width, &key);
+            }
+        }
+        wrefresh(self.win);
+    }
+
+    pub fn refresh(&self) {
+        touchwin(self.win);
+        wrefresh(self.win);
+    }
+}
@@ -9,16 +9,43 @@ extern crate ncurses;
 
 use crate::utils;
 use ncurses::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 // Geometry
 pub const LB_WIDTH: i32 = 3;
 pub const LB_HEIGHT: i32 = 3;
-pub enum Role {
-    UnknownYet,
-    NotInWord,
-    NotInPlace,
-    InPlace,
+pub use wordle_engine::Role;
+
+// Letters marked by their state, to be told apart without colors
+static MARKERS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_markers(on: bool) {
+    MARKERS.store(on, Ordering::SeqCst);
+}
+
+// Attributes of the letter line, the letter in place is underlined
+pub fn marker_attrs(role: Role) -> attr_t {
+    if MARKERS.load(Ordering::SeqCst) && role == Role::InPlace {
+        A_UNDERLINE()
+    } else {
+        A_NORMAL()
+    }
+}
+
+// Glyph in the top right corner, the letter in another place has one
+pub fn marker_glyph(role: Role) -> Option<char> {
+    if MARKERS.load(Ordering::SeqCst) && role == Role::NotInPlace {
+        Some('*')
+    } else {
+        None
+    }
 }
+
+// Letter box sample for legends, " X " or " X*" with the glyph
+pub fn sample(role: Role) -> String {
+    format!(" X{}", marker_glyph(role).unwrap_or(' '))
+}
+
 pub struct Lb {
     pub win: utils::Win,
     border: bool,
@@ -83,26 +110,30 @@ impl Lb {
         );
         wmove(self.win.w, self.top_bw, self.left_bw);
         match self.role {
-            Role::UnknownYet => wattrset(self.win.w, COLOR_PAIR(utils::UNKNOWN_COLOR)),
-            Role::NotInWord => wattrset(self.win.w, COLOR_PAIR(utils::NOT_IN_WORD_COLOR)),
-            Role::NotInPlace => wattrset(self.win.w, COLOR_PAIR(utils::NOT_IN_PLACE_COLOR)),
-            Role::InPlace => wattrset(self.win.w, COLOR_PAIR(utils::IN_PLACE_COLOR)),
+            Role::UnknownYet => wattrset(self.win.w, utils::color(utils::UNKNOWN_COLOR)),
+            Role::NotInWord => wattrset(self.win.w, utils::color(utils::NOT_IN_WORD_COLOR)),
+            Role::NotInPlace => wattrset(self.win.w, utils::color(utils::NOT_IN_PLACE_COLOR)),
+            Role::InPlace => wattrset(self.win.w, utils::color(utils::IN_PLACE_COLOR)),
         };
         for n in 0..(LB_WIDTH - self.top_bw - self.bot_bw) {
             wmove(self.win.w, self.top_bw + n, self.left_bw);
             wprintw(self.win.w, &pad);
         }
-        wmove(self.win.w, LB_HEIGHT / 2, LB_WIDTH / 2);
-        wprintw(self.win.w, &self.c.to_string());
+        if let Some(glyph) = marker_glyph(self.role) {
+            mvwprintw(self.win.w, 0, LB_WIDTH - 1, &glyph.to_string());
+        }
+        wattron(self.win.w, marker_attrs(self.role));
+        mvwprintw(self.win.w, LB_HEIGHT / 2, self.left_bw, &pad);
+        mvwprintw(self.win.w, LB_HEIGHT / 2, LB_WIDTH / 2, &self.c.to_string());
         if self.border {
             self.win.box_();
         } else if matches!(self.role, Role::UnknownYet) {
             wattrset(
                 self.win.w,
                 if self.win.get_focus() {
-                    COLOR_PAIR(utils::FOCUS_COLOR)
+                    utils::color(utils::FOCUS_COLOR)
                 } else {
-                    COLOR_PAIR(utils::NO_FOCUS_COLOR)
+                    utils::color(utils::NO_FOCUS_COLOR)
                 },
             );
             self.win.box_();
@@ -110,6 +141,11 @@ impl Lb {
         wrefresh(self.win.w);
     }
 
+    pub fn relocate(&mut self, x: i32, y: i32) {
+        self.win.relocate(x, y);
+        self.refresh();
+    }
+
     pub fn set_role(&mut self, role: Role) {
         self.set(self.c, role);
     }
@@ -117,4 +153,8 @@ impl Lb {
     pub fn get(&self) -> char {
         self.c
     }
+
+    pub fn get_role(&self) -> Role {
+        self.role
+    }
 }
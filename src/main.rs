@@ -3,13 +3,37 @@
 
 extern crate ncurses;
 
+use error::Error;
 use ncurses::*;
 use rand::Rng;
-use std::sync::mpsc::channel;
+use std::collections::{btree_map, BTreeMap};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+use wordle_engine::{solver, Boards, Game, GuessError, Rules, State};
+mod bench;
+mod browser;
+mod clock;
+mod config;
+mod daily;
+mod dict;
+mod error;
 mod help;
+mod json;
+mod kbd;
 mod lb;
+mod paths;
+mod plain;
+mod save;
+mod share;
+mod stats;
+mod theme;
 mod utils;
 
 use rust_embed::RustEmbed;
@@ -18,11 +42,14 @@ use rust_embed::RustEmbed;
 #[prefix = ""]
 struct Asset;
 
+use structopt::clap;
 use structopt::StructOpt;
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "wordle-ncurses",
-    about = "Ncurses version of the wordle puzzle"
+    about = "Ncurses version of the wordle puzzle",
+    after_help = "Flags turned on in the config file are turned off by --no-<flag>, e.g. \
+                  --no-hard, and --no-assist by --assist."
 )]
 struct Opt {
     /// Verbose mode
@@ -34,85 +61,256 @@ struct Opt {
     debug: bool,
 
     /// Word length
-    #[structopt(short = "w", long = "word", default_value = "5")]
+    #[structopt(
+        short = "w",
+        long = "word",
+        visible_alias = "length",
+        default_value = "5"
+    )]
     wlen: i32,
 
-    /// Amount of attempts
-    #[structopt(short = "t", long = "tries", default_value = "6")]
-    tries: i32,
+    /// Amount of attempts, 6 by default and one more for every extra board,
+    /// 30 in the absurd mode
+    #[structopt(short = "t", long = "tries")]
+    tries: Option<i32>,
+
+    /// Hard mode, revealed hints must be used in subsequent guesses
+    #[structopt(long)]
+    hard: bool,
+
+    /// Daily puzzle, the secret word is derived from the local date
+    #[structopt(long)]
+    daily: bool,
+
+    /// Seed for the secret word selection, the same seed gives the same game
+    #[structopt(long, conflicts_with = "daily")]
+    seed: Option<u64>,
+
+    /// Secret word to play with, e.g. to challenge a friend
+    #[structopt(long, conflicts_with_all = &["daily", "seed"])]
+    secret: Option<String>,
+
+    /// Dictionary file with words accepted as guesses, one word per line
+    #[structopt(long)]
+    dict: Option<String>,
+
+    /// File with words the secret word is selected from, one word per line
+    #[structopt(long)]
+    answers: Option<String>,
+
+    /// Absurd mode, there is no secret word up front, it dodges the guesses
+    #[structopt(long, conflicts_with_all = &["daily", "seed", "secret"])]
+    absurd: bool,
+
+    /// Number of secret words played at once, every guess goes to all of them
+    #[structopt(long, conflicts_with_all = &["secret", "absurd", "plain", "json"])]
+    boards: Option<i32>,
+
+    /// Time limit in seconds, the game is lost when the clock runs out
+    #[structopt(long, conflicts_with_all = &["plain", "json"])]
+    timed: Option<i32>,
+
+    /// Play the given number of puzzles in a row against the clock
+    #[structopt(long, conflicts_with_all = &["secret", "absurd", "plain", "json"])]
+    speedrun: Option<i32>,
+
+    /// Start a new game even if there is a saved one
+    #[structopt(long)]
+    new: bool,
+
+    /// Write the shareable game result to a file
+    #[structopt(long)]
+    share_out: Option<String>,
+
+    /// Copy the shareable game result to the clipboard via a terminal escape sequence
+    #[structopt(long)]
+    share_copy: bool,
+
+    /// Plain text mode without ncurses, a guess is read per line from stdin
+    #[structopt(long)]
+    plain: bool,
+
+    /// Show the plain text mode feedback as [S](h)e markers instead of colors
+    #[structopt(long, requires = "plain")]
+    markers: bool,
+
+    /// Competitive play, no hints and no candidate words browser
+    #[structopt(long)]
+    no_assist: bool,
+
+    /// JSON lines protocol on stdin/stdout for bots, see src/json.rs
+    #[structopt(long, conflicts_with = "plain")]
+    json: bool,
+
+    /// Color theme: classic, dark, light, high-contrast or one defined in the config file
+    #[structopt(long)]
+    theme: Option<String>,
+
+    /// Orange and blue instead of green and yellow, and letters marked by their state
+    #[structopt(long)]
+    colorblind: bool,
+
+    #[structopt(subcommand)]
+    cmd: Option<Cmd>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Cmd {
+    /// Play every answer with a built-in strategy and report the results
+    Bench(BenchOpt),
+}
+
+#[derive(Debug, StructOpt)]
+struct BenchOpt {
+    /// Strategy choosing the guesses
+    #[structopt(long, default_value = "entropy", possible_values = bench::STRATEGIES)]
+    strategy: bench::Strategy,
+
+    /// First guess of every game instead of the strategy choice
+    #[structopt(long)]
+    start: Option<String>,
+
+    /// Number of threads, all processors by default
+    #[structopt(long)]
+    threads: Option<usize>,
+
+    /// Print the results as CSV
+    #[structopt(long)]
+    csv: bool,
+
+    /// Don't print the CSV header line, e.g. to append to a file
+    #[structopt(long, requires = "csv")]
+    no_header: bool,
 }
 
 struct Line {
     lb: Vec<lb::Lb>,
 }
-struct Screen {
+impl Line {
+    pub fn word(&self) -> String {
+        self.lb.iter().map(|lb| lb.get()).collect()
+    }
+}
+// Grid of one board
+struct Grid {
     lines: Vec<Line>,
+    // Solved boards don't take letters anymore
+    frozen: bool,
+}
+// Boards share the focus, letters are typed into all boards not frozen
+struct Screen {
+    grids: Vec<Grid>,
     x_focus: usize,
     y_focus: usize,
 }
 impl Screen {
+    pub fn word_len(&self) -> usize {
+        self.grids[0].lines[0].lb.len()
+    }
+    pub fn rows(&self) -> usize {
+        self.grids[0].lines.len()
+    }
     pub fn refresh(&self) {
-        for line in &self.lines {
-            for lb in &line.lb {
-                lb.refresh();
+        for grid in &self.grids {
+            for line in &grid.lines {
+                for lb in &line.lb {
+                    lb.refresh();
+                }
+            }
+        }
+        mv(LINES() - 1, 0);
+    }
+    // Cells in the focus position of the boards not frozen
+    fn focused(&mut self) -> impl Iterator<Item = &mut lb::Lb> {
+        let (x, y) = (self.x_focus, self.y_focus);
+        self.grids
+            .iter_mut()
+            .filter(|g| !g.frozen)
+            .filter_map(move |g| g.lines[y].lb.get_mut(x))
+    }
+    pub fn set_focus(&mut self, focus: bool) {
+        for lb in self.focused() {
+            lb.win.set_focus(focus);
+            lb.refresh();
+        }
+    }
+    pub fn put(&mut self, c: char) {
+        for lb in self.focused() {
+            lb.set(c, lb::Role::UnknownYet);
+        }
+    }
+    // Letters of the focus row, the same on all boards not frozen
+    pub fn word(&self) -> String {
+        let grid: &Grid = self
+            .grids
+            .iter()
+            .find(|g| !g.frozen)
+            .unwrap_or(&self.grids[0]);
+        grid.lines[self.y_focus].word()
+    }
+    // Empty boards for the next game
+    pub fn reset(&mut self) {
+        self.set_focus(false);
+        for grid in &mut self.grids {
+            grid.frozen = false;
+            for line in &mut grid.lines {
+                for lb in &mut line.lb {
+                    lb.set(' ', lb::Role::UnknownYet);
+                }
+            }
+        }
+        self.x_focus = 0;
+        self.y_focus = 0;
+        self.set_focus(true);
+        self.refresh();
+    }
+    pub fn freeze(&mut self, board: usize) {
+        self.grids[board].frozen = true;
+    }
+    // Move every row one line up freeing the last one
+    pub fn scroll(&mut self) {
+        for grid in &mut self.grids {
+            let lines: &mut Vec<Line> = &mut grid.lines;
+            for y in 1..lines.len() {
+                for x in 0..lines[y].lb.len() {
+                    let c: char = lines[y].lb[x].get();
+                    let role: lb::Role = lines[y].lb[x].get_role();
+                    lines[y - 1].lb[x].set(c, role);
+                }
+            }
+            if let Some(line) = lines.last_mut() {
+                for lb in &mut line.lb {
+                    lb.set(' ', lb::Role::UnknownYet);
+                }
             }
         }
+        self.refresh();
+    }
+    fn move_focus(&mut self, x: usize, y: usize) {
+        self.set_focus(false);
+        self.x_focus = x;
+        self.y_focus = y;
+        self.set_focus(true);
         mv(LINES() - 1, 0);
     }
     pub fn right(&mut self) {
-        if self.x_focus < self.lines[0].lb.len() - 1 {
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(false);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            self.x_focus += 1;
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(true);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            mv(LINES() - 1, 0);
+        if self.x_focus < self.word_len() - 1 {
+            self.move_focus(self.x_focus + 1, self.y_focus);
         }
     }
     pub fn left(&mut self) {
         if self.x_focus > 0 {
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(false);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            self.x_focus -= 1;
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(true);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            mv(LINES() - 1, 0);
+            self.move_focus(self.x_focus - 1, self.y_focus);
         }
     }
     pub fn up(&mut self) {
         if self.y_focus > 0 {
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(false);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            self.y_focus -= 1;
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(true);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            mv(LINES() - 1, 0);
+            self.move_focus(self.x_focus, self.y_focus - 1);
         }
     }
     pub fn down(&mut self) {
-        if self.y_focus < self.lines.len() - 1 {
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(false);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            self.y_focus += 1;
-            self.lines[self.y_focus].lb[self.x_focus]
-                .win
-                .set_focus(true);
-            self.lines[self.y_focus].lb[self.x_focus].refresh();
-            mv(LINES() - 1, 0);
+        if self.y_focus < self.rows() - 1 {
+            self.move_focus(self.x_focus, self.y_focus + 1);
         }
     }
 }
@@ -125,217 +323,1221 @@ fn center(total: i32, len: i32, first: bool) -> i32 {
     }
 }
 
-fn check_word(s: &mut Screen, words: &[String], target_word: &str, debug_mode: bool) -> bool {
-    let mut w: String = String::new();
-    for lb in &s.lines[s.y_focus].lb {
-        w.push(lb.get());
+// Top left corner of the boards area, `bottom` lines are reserved below it
+fn grid_origin(width: i32, height: i32, bottom: i32) -> (i32, i32) {
+    (
+        center(COLS(), width, true),
+        if LINES() > bottom {
+            center(LINES() - bottom, height, false)
+        } else {
+            0
+        },
+    )
+}
+
+// Space between boards, columns and lines
+const BOARD_GAP_X: i32 = 2;
+const BOARD_GAP_Y: i32 = 1;
+
+// Boards side by side, as many as the terminal width allows
+fn board_columns(boards: i32, board_width: i32) -> i32 {
+    ((COLS() + BOARD_GAP_X) / (board_width + BOARD_GAP_X)).clamp(1, boards)
+}
+
+// Recreate all windows for the current terminal size. Returns false and
+// displays a warning instead if the boards don't fit the terminal.
+fn layout(
+    s: &mut Screen,
+    help_win: &mut help::Help,
+    keyboard: &mut kbd::Keyboard,
+    clock: &mut clock::Clock,
+    exit: &str,
+) -> bool {
+    let boards: i32 = s.grids.len() as i32;
+    let board_width: i32 = lb::LB_WIDTH * s.word_len() as i32;
+    let board_height: i32 = lb::LB_HEIGHT * s.rows() as i32;
+    let columns: i32 = board_columns(boards, board_width);
+    let board_rows: i32 = (boards + columns - 1) / columns;
+    let width: i32 = columns * (board_width + BOARD_GAP_X) - BOARD_GAP_X;
+    let height: i32 = board_rows * (board_height + BOARD_GAP_Y) - BOARD_GAP_Y;
+    clear();
+    refresh();
+    help_win.relocate();
+    keyboard.relocate(LINES() - help_win.height - 1);
+    // The keyboard, a free line and the help bar are below the boards
+    let bottom: i32 = help_win.height + 1 + keyboard.height;
+    if COLS() < board_width || LINES() < height + bottom {
+        utils::too_small(board_width, height + bottom, exit);
+        return false;
     }
-    if words.contains(&w) || debug_mode {
-        for (idx, ch) in w.chars().enumerate() {
-            if ch == target_word.chars().nth(idx).unwrap() {
-                s.lines[s.y_focus].lb[idx].set_role(lb::Role::InPlace);
-            } else if target_word.contains(ch) {
-                s.lines[s.y_focus].lb[idx].set_role(lb::Role::NotInPlace);
-            } else {
-                s.lines[s.y_focus].lb[idx].set_role(lb::Role::NotInWord);
+    keyboard.redraw();
+    clock.relocate(LINES() - help_win.height - 1);
+    let (startx, starty) = grid_origin(width, height, bottom);
+    for (n, grid) in s.grids.iter_mut().enumerate() {
+        let left: i32 = startx + (n as i32 % columns) * (board_width + BOARD_GAP_X);
+        let top: i32 = starty + (n as i32 / columns) * (board_height + BOARD_GAP_Y);
+        for (y, line) in grid.lines.iter_mut().enumerate() {
+            for (x, lb) in line.lb.iter_mut().enumerate() {
+                lb.relocate(
+                    left + x as i32 * lb::LB_WIDTH,
+                    top + y as i32 * lb::LB_HEIGHT,
+                );
             }
         }
-        s.refresh();
-        if w == *target_word {
-            utils::msg(
-                "You won!",
-                format!(
-                    "You guessed the right word\n\n          \"{}\"\n\n    From a {}'s attempt!",
-                    w,
-                    s.y_focus + 1
-                )
-                .as_str(),
-                true,
-            );
-            return true;
+    }
+    s.refresh();
+    true
+}
+
+const DEFAULT_TRIES: i32 = 6;
+
+// Second line of the help bar before and after the word is completed
+const HELP_INSERT: &str = "Backspace - Go back, any letter - insert it";
+const HELP_CHECK: &str = " Backspace - Go back, Enter - check the word";
+
+// Words accepted as guesses
+const ALLOWED_FILE: &str = "words_alpha.txt";
+// Common words the secret word is selected from
+const ANSWERS_FILE: &str = "answers.txt";
+
+fn load_words(file: &str, word_len: i32) -> Vec<String> {
+    let words_file = Asset::get(file).unwrap();
+    wordle_engine::words::parse(
+        std::str::from_utf8(words_file.data.as_ref()).unwrap(),
+        word_len,
+    )
+}
+
+// Load a word list given on the command line, the amount of words is added
+// to the report printed on exit
+fn load_user_words(
+    path: &Option<String>,
+    word_len: i32,
+    report: &mut Vec<String>,
+) -> Result<Option<Vec<String>>, Error> {
+    let path = match path {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    let words = dict::load_file(path, word_len)?;
+    report.push(format!(
+        "{}: {} words of {} letters",
+        path,
+        words.len(),
+        word_len
+    ));
+    Ok(Some(words))
+}
+
+// Words accepted as guesses and words the secret word is selected from
+fn prepare_words(
+    user_dict: Option<Vec<String>>,
+    user_answers: Option<Vec<String>>,
+    word_len: i32,
+) -> (Vec<String>, Vec<String>) {
+    let mut words: Vec<String> = user_dict.unwrap_or_else(|| load_words(ALLOWED_FILE, word_len));
+    let mut answers: Vec<String> =
+        user_answers.unwrap_or_else(|| load_words(ANSWERS_FILE, word_len));
+    // Any possible secret word is accepted as a guess
+    words.extend(answers.iter().cloned());
+    words.sort_unstable();
+    words.dedup();
+    if answers.is_empty() {
+        // No common words of such length, use the whole dictionary
+        answers = words.clone();
+    }
+    (words, answers)
+}
+
+fn pick_secret(
+    answers: &[String],
+    word_len: i32,
+    puzzle: Option<i64>,
+    seed: Option<u64>,
+) -> Result<String, Error> {
+    if answers.is_empty() {
+        return Err(Error::NoWords(word_len));
+    }
+    let idx: usize = match (puzzle, seed) {
+        (Some(n), _) => daily::word_index(n, word_len, answers.len()),
+        (None, Some(n)) => daily::seed_index(n, word_len, 0, answers.len()),
+        _ => rand::thread_rng().gen_range(0, answers.len()),
+    };
+    Ok(answers[idx].clone())
+}
+
+// Different secret words for all boards of all puzzles, `count` in total,
+// the first one is the same as pick_secret() gives
+fn pick_secrets(
+    answers: &[String],
+    word_len: i32,
+    puzzle: Option<i64>,
+    seed: Option<u64>,
+    count: i32,
+) -> Result<Vec<String>, Error> {
+    let mut secrets: Vec<String> = vec![pick_secret(answers, word_len, puzzle, seed)?];
+    if answers.len() < count as usize {
+        return Err(Error::NotEnoughWords(answers.len(), word_len, count));
+    }
+    let mut rng = rand::thread_rng();
+    for board in 1..count as usize {
+        let mut idx: usize = match (puzzle, seed) {
+            (Some(n), _) => daily::board_index(n, word_len, board, answers.len()),
+            (None, Some(n)) => daily::seed_index(n, word_len, board, answers.len()),
+            _ => rng.gen_range(0, answers.len()),
+        };
+        while secrets.contains(&answers[idx]) {
+            idx = (idx + 1) % answers.len();
         }
+        secrets.push(answers[idx].clone());
+    }
+    Ok(secrets)
+}
+
+fn check_ranges(word_len: i32, tries: i32) -> Result<(), Error> {
+    if !(1..=error::MAX_WORD_LEN).contains(&word_len) {
+        return Err(Error::WordLength(word_len));
+    }
+    if !(1..=error::MAX_TRIES).contains(&tries) {
+        return Err(Error::Tries(tries));
+    }
+    Ok(())
+}
+
+// One more attempt for every extra board, the most allowed in the absurd
+// mode as the game goes on until a single word is left
+fn default_tries(boards: i32, absurd: bool) -> i32 {
+    if absurd {
+        error::MAX_TRIES
     } else {
-        utils::msg(
-            format!("Word \"{}\"", w).as_str(),
-            "Not in a dictionary",
-            false,
-        );
-        s.refresh();
-        return false;
+        DEFAULT_TRIES + boards - 1
+    }
+}
+
+// Secret word given by the player should be a known word of the right length
+fn check_given_word(
+    w: &str,
+    word_len: i32,
+    user_dict: &Option<Vec<String>>,
+    user_answers: &Option<Vec<String>>,
+    debug: bool,
+) -> Result<(), Error> {
+    if w.chars().count() != word_len as usize {
+        return Err(Error::GivenWordLength(w.to_string(), word_len));
+    }
+    let w: String = w.to_string();
+    let known: bool = match (user_dict, user_answers) {
+        (Some(d), _) if d.contains(&w) => true,
+        (_, Some(a)) if a.contains(&w) => true,
+        (Some(_), _) => false,
+        (None, _) => load_words(ALLOWED_FILE, word_len).contains(&w),
+    };
+    if !debug && !known {
+        return Err(Error::UnknownWord(w));
+    }
+    Ok(())
+}
+
+fn game_id_text(puzzle: Option<i64>, seed: Option<u64>) -> String {
+    match (puzzle, seed) {
+        (Some(n), _) => format!("Daily puzzle #{}", n),
+        (None, Some(n)) => format!("Seed {} (replay with --seed {})", n, n),
+        _ => String::new(),
+    }
+}
+
+// In the absurd mode the secret word is chosen among `answers` while playing
+fn new_game(absurd: bool, secret_word: &str, answers: &[String], tries: i32, hard: bool) -> Game {
+    if absurd {
+        Game::adversarial(answers.to_vec(), tries as usize, Rules { hard })
+    } else {
+        Game::new(secret_word, tries as usize, Rules { hard })
+    }
+}
+
+// Wait for the secret words from the preparation thread
+fn receive_secrets(rx: &Receiver<Result<Vec<String>, Error>>) -> Result<Vec<String>, Error> {
+    rx.recv().map_err(|_| Error::Prepare)?
+}
+
+fn update_keyboard(keyboard: &mut kbd::Keyboard, boards: &Boards) {
+    for c in 'A'..='Z' {
+        keyboard.set(c, boards.letter_role(c));
+    }
+    keyboard.redraw();
+}
+
+// Replay a saved game, its guesses were checked when it was played
+fn restore_game(s: &mut Screen, boards: &mut Boards, g: &save::SavedGame) {
+    s.set_focus(false);
+    for guess in &g.guesses {
+        let _ = boards.guess(guess, None);
+    }
+    // The last guesses are visible if there are more than rows
+    let first: usize = (g.guesses.len() + 1).saturating_sub(s.rows());
+    for (n, game) in boards.games().iter().enumerate() {
+        let rows = game.guesses().get(first..).unwrap_or_default();
+        for (y, (guess, roles)) in rows.iter().enumerate() {
+            for (x, (c, role)) in guess.chars().zip(roles.iter().copied()).enumerate() {
+                s.grids[n].lines[y].lb[x].set(c, role);
+            }
+        }
+        if game.state() != State::InProgress {
+            s.freeze(n);
+        }
+    }
+    s.y_focus = g.guesses.len() - first;
+    s.x_focus = 0;
+    for c in g.current.chars() {
+        s.put(c);
+        s.x_focus += 1;
+    }
+    s.set_focus(true);
+    s.refresh();
+}
+
+// Suggest the next guess. The secret word is expected among the common
+// words unless the feedback rules all of them out.
+fn show_hint(game: &Game, words: &[String], answers: &[String]) {
+    let history = game.guesses();
+    let mut candidates: Vec<String> = solver::candidates(history, answers);
+    if candidates.is_empty() {
+        candidates = solver::candidates(history, words);
+    }
+    let text: String = match solver::best_guess(words, &candidates, game.rules().hard, history) {
+        Some(hint) if candidates.len() == 1 => {
+            format!("The only word left:\n\n    \"{}\"", hint.word)
+        }
+        Some(hint) => format!(
+            "Words left: {}\n\nTry \"{}\", it gives {:.1} bits",
+            candidates.len(),
+            hint.word,
+            hint.bits
+        ),
+        None => "No known word matches the hints".to_string(),
+    };
+    utils::msg("Hint", &text, true);
+}
+
+// Game id and playing time at the end of the game messages
+fn game_footer(game_id: &str, time: Duration) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if !game_id.is_empty() {
+        lines.push(game_id.to_string());
+    }
+    lines.push(format!("Time: {}", clock::format(time)));
+    format!("\n\n    {}", lines.join("\n    "))
+}
+
+// Secret words in quotes
+fn quoted_secrets(boards: &Boards) -> String {
+    let quoted: Vec<String> = boards
+        .games()
+        .iter()
+        .map(|g| format!("\"{}\"", g.secret()))
+        .collect();
+    quoted.join(", ")
+}
+
+// Check the current row, returns true if the game is over
+fn check_word(s: &mut Screen, boards: &mut Boards, words: Option<&[String]>) -> bool {
+    let w: String = s.word();
+    match boards.guess(&w, words) {
+        Ok(feedback) => {
+            for (n, roles) in feedback.into_iter().enumerate() {
+                for (idx, role) in roles.into_iter().flatten().enumerate() {
+                    s.grids[n].lines[s.y_focus].lb[idx].set_role(role);
+                }
+            }
+        }
+        Err(GuessError::HardMode(reason)) => {
+            utils::msg("Hard mode", reason.as_str(), false);
+            s.refresh();
+            return false;
+        }
+        Err(e) => {
+            utils::msg(format!("Word \"{}\"", w).as_str(), &e.to_string(), false);
+            s.refresh();
+            return false;
+        }
     }
     s.refresh();
-    if s.y_focus < s.lines.len() - 1 {
-        if s.x_focus < s.lines[s.y_focus].lb.len() {
-            s.lines[s.y_focus].lb[s.x_focus].win.set_focus(false);
-            s.lines[s.y_focus].lb[s.x_focus].refresh();
+    if boards.state() != State::InProgress {
+        return true;
+    }
+    s.set_focus(false);
+    for (n, game) in boards.games().iter().enumerate() {
+        if game.state() != State::InProgress {
+            s.freeze(n);
         }
-        s.x_focus = 0;
+    }
+    s.x_focus = 0;
+    if s.y_focus < s.rows() - 1 {
         s.y_focus += 1;
-        s.lines[s.y_focus].lb[s.x_focus].win.set_focus(true);
-        s.lines[s.y_focus].lb[s.x_focus].refresh();
-        mv(LINES() - 1, 0);
-        false
     } else {
-        utils::msg(
+        // More attempts than rows on the screen
+        s.scroll();
+    }
+    s.set_focus(true);
+    mv(LINES() - 1, 0);
+    false
+}
+
+// Title and text of the message at the end of the game, a game still in
+// progress is over because the time is up
+fn end_message(boards: &Boards, footer: &str) -> (&'static str, String) {
+    let words: &str = if boards.len() > 1 {
+        "The words are:"
+    } else {
+        "The word is:"
+    };
+    match boards.state() {
+        State::Won(attempt) if boards.len() > 1 => (
+            "You won!",
+            format!(
+                "You guessed all {} words\n\n    From a {}'s attempt!{}",
+                boards.len(),
+                attempt,
+                footer
+            ),
+        ),
+        State::Won(attempt) => (
+            "You won!",
+            format!(
+                "You guessed the right word\n\n          \"{}\"\n\n    From a {}'s attempt!{}",
+                boards.games()[0].secret(),
+                attempt,
+                footer
+            ),
+        ),
+        State::Lost => (
             "You lost!",
-            format!("The word is:\n\n    \"{}\"", target_word).as_str(),
-            true,
+            format!("{}\n\n    {}{}", words, quoted_secrets(boards), footer),
+        ),
+        State::InProgress => (
+            "Time is up!",
+            format!("{}\n\n    {}{}", words, quoted_secrets(boards), footer),
+        ),
+    }
+}
+
+// Statistics of a finished game, absurd and multi-board games don't count,
+// they are much harder
+fn record_game(game_stats: &mut stats::Stats, boards: &Boards, time: Duration, show: bool) {
+    let first: &Game = &boards.games()[0];
+    if first.is_adversarial() || boards.len() > 1 {
+        return;
+    }
+    let (word_len, tries): (i32, i32) = (first.word_len() as i32, first.tries() as i32);
+    let attempt: Option<usize> = winning_attempt(first);
+    game_stats.add(word_len, tries, attempt, Some(time));
+    if let Err(e) = game_stats.save() {
+        utils::msg("Statistics", &format!("Can't save: {}", e), true);
+    }
+    if show {
+        stats::show(game_stats, word_len, tries, attempt);
+    }
+}
+
+// Speedrun summary, it is recorded if `record` is set
+fn speedrun_result(
+    game_stats: &mut stats::Stats,
+    word_len: i32,
+    puzzles: usize,
+    solved: usize,
+    time: Duration,
+    record: bool,
+) {
+    let total: Option<Duration> = if solved == puzzles { Some(time) } else { None };
+    if record {
+        game_stats.add_speedrun(word_len, puzzles as u32, total);
+        if let Err(e) = game_stats.save() {
+            utils::msg("Statistics", &format!("Can't save: {}", e), true);
+        }
+    }
+    let mut text: String = format!("Solved {} of {} puzzles", solved, puzzles);
+    if total.is_some() {
+        text.push_str(&format!("\n\n    Time: {}", clock::format(time)));
+    }
+    if let Some(r) = game_stats.get_speedrun(word_len, puzzles as u32) {
+        if r.completed > 0 {
+            text.push_str(&format!(
+                "\n    Best: {}",
+                clock::format(Duration::from_secs(r.best_secs))
+            ));
+        }
+    }
+    utils::msg("Speedrun", &text, true);
+}
+
+fn winning_attempt(game: &Game) -> Option<usize> {
+    match game.state() {
+        State::Won(n) => Some(n),
+        _ => None,
+    }
+}
+
+// Print the word list report and the shareable result after the game
+fn print_results(opt: &Opt, report: &[String], share_text: Option<String>) {
+    for line in report {
+        eprintln!("{}", line);
+    }
+    if let Some(text) = share_text {
+        print!("{}", text);
+        if let Some(path) = &opt.share_out {
+            if let Err(e) = fs::write(path, &text) {
+                eprintln!("Can't write \"{}\": {}", path, e);
+            }
+        }
+        if opt.share_copy {
+            print!("{}", share::osc52(&text));
+        }
+        let _ = std::io::stdout().flush();
+    }
+}
+
+// Line oriented game without ncurses
+fn run_plain(
+    opt: &Opt,
+    game: &mut Game,
+    words: Option<&[String]>,
+    puzzle: Option<i64>,
+    game_id: &str,
+    report: &[String],
+) -> Result<(), Error> {
+    let style: plain::Style = if opt.markers {
+        plain::Style::Markers
+    } else {
+        plain::Style::detect(opt.colorblind)
+    };
+    if opt.debug {
+        println!("Secret word: {}", game.secret());
+    }
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    plain::play(
+        stdin.lock(),
+        &mut stdout.lock(),
+        game,
+        words,
+        style,
+        game_id,
+    )
+    .map_err(Error::Io)?;
+    if game.state() == State::InProgress {
+        print_results(opt, report, None);
+        return Ok(());
+    }
+    if !opt.debug && !game.is_adversarial() {
+        let mut game_stats: stats::Stats = stats::Stats::load();
+        game_stats.add(
+            game.word_len() as i32,
+            game.tries() as i32,
+            winning_attempt(game),
+            None,
         );
-        true
+        if let Err(e) = game_stats.save() {
+            eprintln!("Can't save statistics: {}", e);
+        }
     }
+    print_results(opt, report, Some(share::game_text(game, puzzle)));
+    Ok(())
+}
+
+// Words accepted as guesses and words the secret word is selected from by
+// the word length, prepared once for all JSON games
+type WordLists = BTreeMap<i32, (Rc<[String]>, Vec<String>)>;
+
+// Game for the JSON protocol, settings missing in `ng` are taken from the command line
+fn json_setup(opt: &Opt, ng: json::NewGame, lists: &mut WordLists) -> Result<json::Setup, Error> {
+    let word_len: i32 = ng.length.unwrap_or(opt.wlen);
+    let tries: i32 = ng
+        .tries
+        .or(opt.tries)
+        .unwrap_or(default_tries(1, opt.absurd));
+    check_ranges(word_len, tries)?;
+    if opt.absurd && (ng.daily.is_some() || ng.seed.is_some() || ng.word.is_some()) {
+        return Err(Error::AbsurdSecret);
+    }
+    // The secret word choice is taken either all from the command or all from the command line
+    let (daily, seed, word): (bool, Option<u64>, Option<String>) =
+        if ng.daily.is_some() || ng.seed.is_some() || ng.word.is_some() {
+            (ng.daily.unwrap_or(false), ng.seed, ng.word)
+        } else {
+            (opt.daily, opt.seed, opt.secret.clone())
+        };
+    let puzzle: Option<i64> = if daily { Some(daily::today()) } else { None };
+    if let btree_map::Entry::Vacant(entry) = lists.entry(word_len) {
+        let mut report: Vec<String> = Vec::new();
+        let user_dict: Option<Vec<String>> = load_user_words(&opt.dict, word_len, &mut report)?;
+        let user_answers: Option<Vec<String>> =
+            load_user_words(&opt.answers, word_len, &mut report)?;
+        let (words, answers) = prepare_words(user_dict, user_answers, word_len);
+        entry.insert((words.into(), answers));
+    }
+    let (words, answers) = &lists[&word_len];
+    let word: Option<String> = word.map(|w| w.to_uppercase());
+    if let Some(w) = &word {
+        // The prepared words are sorted and have the answers among them
+        if w.chars().count() != word_len as usize {
+            return Err(Error::GivenWordLength(w.to_string(), word_len));
+        }
+        if !opt.debug && words.binary_search(w).is_err() {
+            return Err(Error::UnknownWord(w.to_string()));
+        }
+    }
+    let secret_word: String = match word {
+        Some(w) => w,
+        None => pick_secret(answers, word_len, puzzle, seed)?,
+    };
+    Ok(json::Setup {
+        game: new_game(
+            opt.absurd,
+            &secret_word,
+            answers,
+            tries,
+            ng.hard.unwrap_or(opt.hard),
+        ),
+        words: if opt.debug {
+            None
+        } else {
+            Some(Rc::clone(words))
+        },
+        puzzle,
+        game_id: game_id_text(puzzle, seed),
+    })
+}
+
+fn run_json(opt: &Opt) -> Result<(), Error> {
+    let mut lists: WordLists = WordLists::new();
+    let setup: json::Setup = json_setup(opt, json::NewGame::default(), &mut lists)?;
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    json::serve(stdin.lock(), &mut stdout.lock(), setup, |ng| {
+        json_setup(opt, ng, &mut lists).map_err(|e| e.to_string())
+    })
+    .map_err(Error::Io)
+}
+
+// Autoplay benchmark, word length, attempts, hard mode, seed and word lists
+// are taken from the common options
+fn run_bench(opt: &Opt, b: &BenchOpt) -> Result<(), Error> {
+    let tries: i32 = opt.tries.unwrap_or(DEFAULT_TRIES);
+    check_ranges(opt.wlen, tries)?;
+    let mut report: Vec<String> = Vec::new();
+    let user_dict: Option<Vec<String>> = load_user_words(&opt.dict, opt.wlen, &mut report)?;
+    let user_answers: Option<Vec<String>> = load_user_words(&opt.answers, opt.wlen, &mut report)?;
+    let start: Option<String> = b.start.as_ref().map(|w| w.to_uppercase());
+    if let Some(w) = &start {
+        check_given_word(w, opt.wlen, &user_dict, &user_answers, opt.debug)?;
+    }
+    let (words, answers) = prepare_words(user_dict, user_answers, opt.wlen);
+    if answers.is_empty() {
+        return Err(Error::NoWords(opt.wlen));
+    }
+    let mut setup = bench::Setup {
+        strategy: b.strategy,
+        start,
+        hard: opt.hard,
+        seed: opt.seed.unwrap_or(0),
+        words: &words,
+        answers: &answers,
+    };
+    // The opening is the same for every game
+    if setup.start.is_none() && setup.strategy == bench::Strategy::Entropy {
+        setup.start = solver::best_guess(&words, &answers, opt.hard, &[]).map(|h| h.word);
+    }
+    let threads: usize = b
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let results: Vec<Option<usize>> = bench::run(&setup, threads);
+    let summary = bench::Summary::new(&results, tries as usize);
+    for line in report {
+        eprintln!("{}", line);
+    }
+    if b.csv {
+        if !b.no_header {
+            println!("{}", bench::Summary::csv_header(tries as usize));
+        }
+        println!("{}", summary.csv_row(&setup, opt.wlen));
+    } else {
+        println!(
+            "Strategy: {}, start word: {}, {} words of {} letters, {} attempts{}",
+            setup.strategy,
+            setup.start.as_deref().unwrap_or("-"),
+            answers.len(),
+            opt.wlen,
+            tries,
+            if opt.hard { ", hard mode" } else { "" }
+        );
+        print!("{}", summary.text());
+    }
+    Ok(())
+}
+
+// Flags which may be turned on in the config file and the options turning
+// them off on the command line
+const NEGATED_FLAGS: [(&str, &str); 12] = [
+    ("verbose", "no-verbose"),
+    ("debug", "no-debug"),
+    ("hard", "no-hard"),
+    ("daily", "no-daily"),
+    ("absurd", "no-absurd"),
+    ("new", "no-new"),
+    ("share-copy", "no-share-copy"),
+    ("plain", "no-plain"),
+    ("markers", "no-markers"),
+    ("no-assist", "assist"),
+    ("json", "no-json"),
+    ("colorblind", "no-colorblind"),
+];
+
+// Command line options with the config file defaults for the ones not
+// given. A default conflicting with the command line is dropped, defaults
+// conflicting with each other are an error naming the key.
+fn parse_args(args: &[OsString], config: &config::Config) -> Result<Opt, Error> {
+    // The last one of a flag and its negation wins, the defaults go first
+    let negations: Vec<clap::Arg> = NEGATED_FLAGS
+        .iter()
+        .map(|&(flag, negation)| {
+            clap::Arg::with_name(negation)
+                .long(negation)
+                .overrides_with(flag)
+                .hidden(true)
+        })
+        .collect();
+    let parse = |defaults: &[String]| {
+        let mut all: Vec<OsString> = args.iter().take(1).cloned().collect();
+        all.extend(defaults.iter().map(OsString::from));
+        all.extend(args.iter().skip(1).cloned());
+        Opt::clap().args(&negations).get_matches_from_safe(all)
+    };
+    if let Err(e) = parse(&[]) {
+        e.exit();
+    }
+    let mut defaults: Vec<String> = Vec::new();
+    for (key, arg) in config.args() {
+        if parse(std::slice::from_ref(&arg)).is_err() {
+            continue;
+        }
+        let mut with: Vec<String> = defaults.clone();
+        with.push(arg);
+        match parse(&with) {
+            Ok(_) => defaults = with,
+            Err(e) => {
+                let reason: &str = e.message.lines().next().unwrap_or_default();
+                return Err(Error::Config(
+                    config.path.clone(),
+                    format!("{} for key `{}`", reason.trim_start_matches("error: "), key),
+                ));
+            }
+        }
+    }
+    Ok(Opt::from_clap(
+        &parse(&defaults).unwrap_or_else(|e| e.exit()),
+    ))
 }
 
 fn main() {
-    let opt = Opt::from_args();
-    let word_len: i32 = opt.wlen;
+    let args: Vec<OsString> = env::args_os().collect();
+    let result: Result<(), Error> = config::Config::load().and_then(|config| {
+        let opt: Opt = parse_args(&args, &config)?;
+        utils::set_theme(config.theme(opt.theme.as_deref(), opt.colorblind)?);
+        lb::set_markers(opt.colorblind);
+        run(opt, &config.keys)
+    });
+    if let Err(e) = result {
+        utils::end();
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(opt: Opt, keys: &config::Keys) -> Result<(), Error> {
+    let mut word_len: i32 = opt.wlen;
     let verbose: bool = opt.verbose;
     let debug: bool = opt.debug;
-    let tries: i32 = opt.tries;
+    let mut boards: i32 = opt.boards.unwrap_or(1);
+    let mut tries: i32 = opt.tries.unwrap_or(default_tries(boards, opt.absurd));
+    let mut hard: bool = opt.hard;
+    let mut absurd: bool = opt.absurd;
+    let mut report: Vec<String> = Vec::new();
+    if let Some(Cmd::Bench(b)) = &opt.cmd {
+        return run_bench(&opt, b);
+    }
+    if opt.json {
+        return run_json(&opt);
+    }
+
+    check_ranges(word_len, tries)?;
+    if !(1..=error::MAX_BOARDS).contains(&boards) {
+        return Err(Error::Boards(boards));
+    }
+    let puzzles: i32 = opt.speedrun.unwrap_or(1);
+    if !(1..=error::MAX_PUZZLES).contains(&puzzles) {
+        return Err(Error::Puzzles(puzzles));
+    }
+    if let Some(n) = opt
+        .timed
+        .filter(|n| !(1..=error::MAX_TIME_LIMIT).contains(n))
+    {
+        return Err(Error::TimeLimit(n));
+    }
+    let mut puzzle: Option<i64> = if opt.daily {
+        Some(daily::today())
+    } else {
+        None
+    };
+    let mut seed: Option<u64> = opt.seed;
+    let mut given_word: Option<String> = opt.secret.as_ref().map(|w| w.to_uppercase());
+    let mut user_dict: Option<Vec<String>> = load_user_words(&opt.dict, word_len, &mut report)?;
+    let mut user_answers: Option<Vec<String>> =
+        load_user_words(&opt.answers, word_len, &mut report)?;
+    if let Some(w) = &given_word {
+        check_given_word(w, word_len, &user_dict, &user_answers, debug)?;
+    }
+
+    // Offer to continue a game saved on exit, the options are checked
+    // before as they are used otherwise
+    let mut resumed: Option<save::SavedGame> = match save::SavedGame::load() {
+        Some(g) if !opt.new && !opt.plain && opt.timed.is_none() && opt.speedrun.is_none() => {
+            utils::init();
+            let yes: bool = utils::yes_no("Saved game", "Do you want to continue the saved game?");
+            if yes || utils::yes_no("Saved game", "Discard the saved game?") {
+                save::SavedGame::remove();
+            }
+            clear();
+            refresh();
+            if yes {
+                Some(g)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    if let Some(g) = &resumed {
+        tries = g.tries;
+        hard = g.hard;
+        absurd = g.absurd;
+        boards = g.secrets.len() as i32;
+        puzzle = g.puzzle;
+        seed = g.seed;
+        given_word = None;
+        check_ranges(g.wlen, tries)?;
+        if g.wlen != word_len {
+            word_len = g.wlen;
+            report.clear();
+            user_dict = load_user_words(&opt.dict, word_len, &mut report)?;
+            user_answers = load_user_words(&opt.answers, word_len, &mut report)?;
+        }
+    }
+    let mut game_id: String = game_id_text(puzzle, seed);
     let words: Vec<String> = Vec::new();
     let words1_mtx = Arc::new(Mutex::new(words));
     let words2_mtx = Arc::clone(&words1_mtx);
-    let mut secret_word: String = String::new();
+    let answers1_mtx = Arc::new(Mutex::new(Vec::new()));
+    let answers2_mtx = Arc::clone(&answers1_mtx);
 
-    let (tx, rx) = channel();
+    let secret_words: Option<Vec<String>> = match &resumed {
+        Some(g) => Some(g.secrets.clone()),
+        None => given_word.map(|w| vec![w]),
+    };
+    let (tx, rx) = channel::<Result<Vec<String>, Error>>();
     let prepare_handle = thread::spawn(move || {
         let tx = tx.clone();
-        let words_file = Asset::get("words_alpha.txt").unwrap();
         let mut words = words1_mtx.lock().unwrap();
-        *words = std::str::from_utf8(words_file.data.as_ref())
-            .unwrap()
-            .to_string()
-            .split('\n')
-            .filter(|&x| x.len() == word_len as usize)
-            .map(|x| x.to_uppercase())
-            .collect();
-        let mut rng = rand::thread_rng();
-        tx.send((*words)[rng.gen_range(0, (*words).len())].clone())
-            .unwrap();
+        let mut answers = answers1_mtx.lock().unwrap();
+        (*words, *answers) = prepare_words(user_dict, user_answers, word_len);
+        tx.send(match secret_words {
+            Some(w) => Ok(w),
+            None => pick_secrets(&answers, word_len, puzzle, seed, boards * puzzles),
+        })
+        .unwrap();
     });
 
+    if opt.plain {
+        let secret_words: Vec<String> = receive_secrets(&rx)?;
+        let words = words2_mtx.lock().unwrap();
+        let mut game: Game = new_game(
+            absurd,
+            &secret_words[0],
+            &answers2_mtx.lock().unwrap(),
+            tries,
+            hard,
+        );
+        return run_plain(
+            &opt,
+            &mut game,
+            if debug { None } else { Some(&words) },
+            puzzle,
+            &game_id,
+            &report,
+        );
+    }
+
     // Init ncurses
     utils::init();
 
     // Help bottom panel
-    let help_not_completed: &str =
-        "F1 - Help, F10 - Exit, Backspace - Go back\n  Any alphabetic character - insert it";
-    let help_completed: &str =
-        "F1 - Help, F10 - Exit, Backspace - Go back\n           Enter - Check the word";
-    let help_win: help::Help = help::Help::new(help_completed, false);
-    help_win.redraw(help_not_completed);
-
-    // Main window
-    let mut startx: i32 = center(COLS(), lb::LB_WIDTH * word_len, true);
-    let mut starty: i32 = if LINES() > help_win.height {
-        center(LINES() - help_win.height, lb::LB_HEIGHT * tries, false)
+    let assist: bool = !opt.no_assist;
+    let exit_name: String = keys.exit.name();
+    let keys_text: String = if assist {
+        format!(
+            "{} - Help, {} - Hint, {} - Stats, {} - Words, {} - Exit",
+            keys.help.name(),
+            keys.hint.name(),
+            keys.stats.name(),
+            keys.words.name(),
+            exit_name
+        )
     } else {
-        0
+        format!(
+            "      {} - Help, {} - Stats, {} - Exit",
+            keys.help.name(),
+            keys.stats.name(),
+            exit_name
+        )
+    };
+    let mut help_win: help::Help =
+        help::Help::new(&format!("{}\n{}", keys_text, HELP_CHECK), false);
+    help_win.redraw(&format!("{}\n{}", keys_text, HELP_INSERT));
+
+    // Keyboard panel above the help, one empty line between them
+    let mut keyboard: kbd::Keyboard = kbd::Keyboard::new(LINES() - help_win.height - 1);
+    keyboard.redraw();
+
+    // Main window, the absurd game scrolls if it goes on longer
+    let rows: i32 = if absurd {
+        tries.min(DEFAULT_TRIES)
+    } else {
+        tries
     };
     let mut screen: Screen = Screen {
-        lines: Vec::new(),
+        grids: Vec::new(),
         x_focus: 0,
         y_focus: 0,
     };
-    for y in 0..tries {
-        let mut line: Line = Line { lb: Vec::new() };
-        for x in 0..word_len {
-            line.lb
-                .push(lb::Lb::new(false, y == 0 && x == 0, startx, starty));
-            startx += lb::LB_WIDTH;
-        }
-        startx = center(COLS(), lb::LB_WIDTH * word_len, true);
-        starty += lb::LB_HEIGHT;
-        screen.lines.push(line);
-    }
-    screen.refresh();
-
-    loop {
-        if screen.x_focus >= screen.lines[screen.y_focus].lb.len() {
-            help_win.redraw(help_completed);
-        } else {
-            help_win.redraw(help_not_completed);
+    // Cells are placed by layout()
+    for _ in 0..boards {
+        let mut grid: Grid = Grid {
+            lines: Vec::new(),
+            frozen: false,
+        };
+        for y in 0..rows {
+            let mut line: Line = Line { lb: Vec::new() };
+            for x in 0..word_len {
+                line.lb.push(lb::Lb::new(false, y == 0 && x == 0, 0, 0));
+            }
+            grid.lines.push(line);
         }
+        screen.grids.push(grid);
+    }
+    // Clock on the empty line above the help
+    let mut clock: clock::Clock = clock::Clock::new(
+        opt.timed.map(|n| Duration::from_secs(n as u64)),
+        opt.timed.is_some() || opt.speedrun.is_some(),
+        LINES() - help_win.height - 1,
+    );
+    let mut fits: bool = layout(
+        &mut screen,
+        &mut help_win,
+        &mut keyboard,
+        &mut clock,
+        &exit_name,
+    );
+    let all_secrets: Vec<String> = receive_secrets(&rx)?;
+    let puzzles: usize = all_secrets.len() / boards as usize;
+    let mut game_stats: stats::Stats = stats::Stats::load();
+    let mut shares: Vec<String> = Vec::new();
+    let mut quit: bool = false;
+    // Games won and played in this session
+    let mut score: (usize, usize) = (0, 0);
 
-        let ch = getch();
-        let ch_as_char: char = if ch < 256 {
-            std::char::from_u32(ch as u32).unwrap_or('0')
+    // Puzzles of a speedrun one after another, then new games until quit
+    'session: for n in 0.. {
+        let secret_words: Vec<String> = match all_secrets.chunks(boards as usize).nth(n) {
+            Some(w) => w.to_vec(),
+            None => pick_secrets(&answers2_mtx.lock().unwrap(), word_len, None, None, boards)?,
+        };
+        if puzzles > 1 {
+            clock.set_label(&format!("Puzzle {}/{}", n + 1, puzzles));
+        }
+        let mut game: Boards = {
+            let answers = answers2_mtx.lock().unwrap();
+            Boards::new(
+                secret_words
+                    .iter()
+                    .map(|w| new_game(absurd, w, &answers, tries, hard))
+                    .collect(),
+            )
+        };
+        if let Some(g) = resumed.take() {
+            restore_game(&mut screen, &mut game, &g);
+            update_keyboard(&mut keyboard, &game);
+        }
+        let started: Instant = Instant::now();
+        let keys_line: String = if score.1 > 0 {
+            format!("{}   Won {}/{}", keys_text, score.0, score.1)
         } else {
-            '0'
+            keys_text.clone()
         };
-        if ch == KEY_F(10) {
-            if utils::yes_no(
-                "Exit confirmation",
-                "Do you really want to quit from the wordle?",
-            ) {
-                break;
-            }
-            screen.refresh();
-            help_win.refresh();
-        } else if ch == KEY_F(1) {
-            if secret_word.is_empty() {
-                secret_word = rx.recv().unwrap();
+        let help_not_completed: String = format!("{}\n{}", keys_line, HELP_INSERT);
+        let help_completed: String = format!("{}\n{}", keys_line, HELP_CHECK);
+        help_win.fit(&help_completed);
+
+        // Returns true if the time is up
+        let time_up: bool = loop {
+            if utils::take_resized() || !fits {
+                fits = layout(
+                    &mut screen,
+                    &mut help_win,
+                    &mut keyboard,
+                    &mut clock,
+                    &exit_name,
+                );
             }
-            help::detailed_help(debug, &secret_word);
-            screen.refresh();
-            help_win.refresh();
-        } else if ch == KEY_ENTER || ch_as_char == '\n' {
-            if screen.x_focus >= screen.lines[screen.y_focus].lb.len() {
-                if secret_word.is_empty() {
-                    secret_word = rx.recv().unwrap();
+            if fits {
+                if screen.x_focus >= screen.word_len() {
+                    help_win.redraw(&help_completed);
+                } else {
+                    help_win.redraw(&help_not_completed);
                 }
+                clock.redraw();
+            }
 
-                if check_word(
-                    &mut screen,
-                    &words2_mtx.lock().unwrap(),
-                    &secret_word,
-                    debug,
+            // Wake up regularly to update the clock
+            if clock.is_shown() {
+                timeout(clock::TICK_MS);
+            }
+            let ch = getch();
+            timeout(-1);
+            if clock.is_over() {
+                break true;
+            }
+            if ch == ERR {
+                continue;
+            }
+            if ch == KEY_RESIZE {
+                utils::set_resized();
+                continue;
+            }
+            if !fits && ch != keys.exit.0 {
+                // Only exit is possible until the terminal is large enough
+                continue;
+            }
+            let ch_as_char: char = if ch < 256 {
+                std::char::from_u32(ch as u32).unwrap_or('0')
+            } else {
+                '0'
+            };
+            if ch == keys.exit.0 {
+                if utils::yes_no(
+                    "Exit confirmation",
+                    "Do you really want to quit from the wordle?",
                 ) {
-                    break;
+                    quit = true;
+                    // Keep the unfinished game for the next launch, games
+                    // against the clock can't be continued
+                    if clock.is_shown() {
+                        break 'session;
+                    }
+                    let x_end: usize = std::cmp::min(screen.x_focus, word_len as usize);
+                    let g = save::SavedGame {
+                        wlen: word_len,
+                        tries,
+                        secrets: game
+                            .games()
+                            .iter()
+                            .map(|g| g.secret().to_string())
+                            .collect(),
+                        hard,
+                        absurd,
+                        puzzle,
+                        seed,
+                        guesses: game.guesses(),
+                        current: screen.word().chars().take(x_end).collect(),
+                    };
+                    // A save kept at the start or with --new isn't replaced
+                    // without asking
+                    let played: bool = !g.guesses.is_empty() || !g.current.is_empty();
+                    if played
+                        && (save::SavedGame::load().is_none()
+                            || utils::yes_no("Saved game", "Replace the saved game with this one?"))
+                    {
+                        if let Err(e) = g.save() {
+                            utils::msg("Saved game", &format!("Can't save: {}", e), true);
+                        }
+                    }
+                    break 'session;
                 }
-            }
-        } else if ch == KEY_BACKSPACE {
-            if screen.x_focus > 0 {
-                if screen.x_focus < screen.lines[0].lb.len() {
-                    screen.lines[screen.y_focus].lb[screen.x_focus].set(' ', lb::Role::UnknownYet);
-                    screen.lines[screen.y_focus].lb[screen.x_focus]
-                        .win
-                        .set_focus(false);
-                    screen.lines[screen.y_focus].lb[screen.x_focus].refresh();
+                screen.refresh();
+                help_win.refresh();
+                keyboard.refresh();
+            } else if ch == keys.help.0 {
+                let secrets: Vec<&str> = game.games().iter().map(|g| g.secret()).collect();
+                help::detailed_help(debug, assist, &secrets, keys);
+                screen.refresh();
+                help_win.refresh();
+                keyboard.refresh();
+            } else if ch == keys.hint.0 && assist {
+                show_hint(
+                    game.current(),
+                    &words2_mtx.lock().unwrap(),
+                    &answers2_mtx.lock().unwrap(),
+                );
+                screen.refresh();
+                help_win.refresh();
+                keyboard.refresh();
+            } else if ch == keys.words.0 && assist {
+                let candidates: Vec<String> =
+                    solver::candidates(game.current().guesses(), &words2_mtx.lock().unwrap());
+                browser::show(&candidates, &[keys.words.0, keys.exit.0]);
+                screen.refresh();
+                help_win.refresh();
+                keyboard.refresh();
+            } else if ch == keys.stats.0 {
+                stats::show(&game_stats, word_len, tries, None);
+                screen.refresh();
+                help_win.refresh();
+                keyboard.refresh();
+            } else if ch == KEY_ENTER || ch_as_char == '\n' {
+                if screen.x_focus >= screen.word_len() {
+                    // No dictionary check in the debug mode
+                    let words = words2_mtx.lock().unwrap();
+                    let done: bool = check_word(
+                        &mut screen,
+                        &mut game,
+                        if debug { None } else { Some(&words) },
+                    );
+                    update_keyboard(&mut keyboard, &game);
+                    if done {
+                        break false;
+                    }
                 }
-                screen.x_focus -= 1;
-                screen.lines[screen.y_focus].lb[screen.x_focus].set(' ', lb::Role::UnknownYet);
-                screen.lines[screen.y_focus].lb[screen.x_focus]
-                    .win
-                    .set_focus(true);
-                screen.lines[screen.y_focus].lb[screen.x_focus].refresh();
-                mv(LINES() - 1, 0);
-            }
-        } else if ch_as_char.is_alphabetic() {
-            if screen.x_focus < screen.lines[screen.y_focus].lb.len() {
-                screen.lines[screen.y_focus].lb[screen.x_focus]
-                    .set(ch_as_char, lb::Role::UnknownYet);
-                screen.lines[screen.y_focus].lb[screen.x_focus]
-                    .win
-                    .set_focus(false);
-                screen.lines[screen.y_focus].lb[screen.x_focus].refresh();
-                screen.x_focus += 1;
-                if screen.x_focus < screen.lines[screen.y_focus].lb.len() {
-                    screen.lines[screen.y_focus].lb[screen.x_focus]
-                        .win
-                        .set_focus(true);
-                    screen.lines[screen.y_focus].lb[screen.x_focus].refresh();
+            } else if ch == KEY_BACKSPACE {
+                if screen.x_focus > 0 {
+                    screen.put(' ');
+                    screen.set_focus(false);
+                    screen.x_focus -= 1;
+                    screen.put(' ');
+                    screen.set_focus(true);
+                    mv(LINES() - 1, 0);
+                }
+            } else if ch_as_char.is_ascii_alphabetic() {
+                if screen.x_focus < screen.word_len() {
+                    screen.put(ch_as_char);
+                    screen.set_focus(false);
+                    screen.x_focus += 1;
+                    screen.set_focus(true);
+                    mv(LINES() - 1, 0);
+                }
+            } else if debug {
+                match ch {
+                    KEY_LEFT => screen.left(),
+                    KEY_RIGHT => screen.right(),
+                    KEY_UP => screen.up(),
+                    KEY_DOWN => screen.down(),
+                    _ => {}
                 }
-                mv(LINES() - 1, 0);
-            }
-        } else if debug {
-            match ch {
-                KEY_LEFT => screen.left(),
-                KEY_RIGHT => screen.right(),
-                KEY_UP => screen.up(),
-                KEY_DOWN => screen.down(),
-                _ => {}
             }
+        };
+
+        // The dialogs after a game don't count for the speedrun time
+        clock.pause();
+        let time: Duration = started.elapsed();
+        let (title, text) = end_message(&game, &game_footer(&game_id, time));
+        shares.push(share::boards_text(game.games(), puzzle));
+        score.1 += 1;
+        if let State::Won(_) = game.state() {
+            score.0 += 1;
+        }
+        // Speedrun puzzles go on until the last one or the end of time
+        let again: bool = if puzzles > 1 {
+            utils::msg(title, &text, true);
+            n + 1 < puzzles && !time_up
+        } else {
+            utils::choice(title, &text, &["New game", "Quit"]) == 0
+        };
+        // The statistics are shown on quit, after a speedrun its result is
+        if !debug {
+            record_game(&mut game_stats, &game, time, !again && puzzles == 1);
+        }
+        if !again {
+            break;
         }
+
+        // Next games are random ones
+        if n + 1 >= puzzles {
+            puzzle = None;
+            seed = None;
+            game_id = String::new();
+            clock.restart();
+        }
+        clock.resume();
+        screen.reset();
+        keyboard.reset();
+        keyboard.redraw();
+    }
+    if puzzles > 1 && !quit {
+        speedrun_result(
+            &mut game_stats,
+            word_len,
+            puzzles,
+            score.0,
+            clock.elapsed(),
+            !debug && boards == 1,
+        );
     }
     utils::end();
+    print_results(
+        &opt,
+        &report,
+        if shares.is_empty() {
+            None
+        } else {
+            Some(shares.join("\n"))
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &str, args: &str) -> Result<Opt, Error> {
+        let config = config::Config::parse(config).unwrap();
+        let args: Vec<OsString> = std::iter::once("wordle-ncurses")
+            .chain(args.split_whitespace())
+            .map(OsString::from)
+            .collect();
+        parse_args(&args, &config)
+    }
+
+    #[test]
+    fn config_defaults() {
+        let opt: Opt = parse("length = 6\nhard = true\n", "--tries 4").unwrap();
+        assert_eq!((opt.wlen, opt.tries, opt.hard), (6, Some(4), true));
+        // The command line wins over a conflicting default
+        let opt: Opt = parse("daily = true\n", "--seed 7").unwrap();
+        assert_eq!((opt.daily, opt.seed), (false, Some(7)));
+        match parse("daily = true\nseed = 7\n", "") {
+            Err(Error::Config(_, reason)) => assert!(reason.ends_with("for key `seed`")),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn negated_flags() {
+        assert!(!parse("hard = true\n", "--no-hard").unwrap().hard);
+        assert!(parse("hard = true\n", "--no-hard --hard").unwrap().hard);
+        assert!(!parse("", "--hard --no-hard").unwrap().hard);
+        assert!(!parse("no-assist = true\n", "--assist").unwrap().no_assist);
+        assert!(
+            !parse("share-copy = true\n", "--no-share-copy")
+                .unwrap()
+                .share_copy
+        );
+    }
 }
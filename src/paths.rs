@@ -0,0 +1,27 @@
+/*
+ * File locations
+ * --------------
+ */
+
+use std::env;
+use std::path::PathBuf;
+
+// File in $XDG_DATA_HOME/wordle-ncurses, ~/.local/share is used if the
+// variable is not set
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    let dir: PathBuf = match env::var_os("XDG_DATA_HOME") {
+        Some(d) if !d.is_empty() => PathBuf::from(d),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(dir.join("wordle-ncurses").join(name))
+}
+
+// File in $XDG_CONFIG_HOME/wordle-ncurses, ~/.config is used if the
+// variable is not set
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    let dir: PathBuf = match env::var_os("XDG_CONFIG_HOME") {
+        Some(d) if !d.is_empty() => PathBuf::from(d),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("wordle-ncurses").join(name))
+}
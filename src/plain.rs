@@ -0,0 +1,173 @@
+/*
+ * Plain text mode
+ * ---------------
+ * Line oriented game over stdin/stdout for dumb terminals, serial consoles
+ * and scripts. A guess is read per line, the feedback is printed either with
+ * ANSI colors or with markers: [S] in place, (h) misplaced, e not in word.
+ */
+
+use std::io::{self, BufRead, Write};
+use wordle_engine::{Game, Role, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Ansi,
+    // Orange and blue instead of green and yellow
+    Colorblind,
+    Markers,
+}
+
+impl Style {
+    // Colors only for a terminal which is able to display them
+    pub fn detect(colorblind: bool) -> Self {
+        let dumb: bool = std::env::var("TERM").map_or(true, |t| t == "dumb");
+        if io::IsTerminal::is_terminal(&io::stdout())
+            && !dumb
+            && std::env::var_os("NO_COLOR").is_none()
+        {
+            if colorblind {
+                Style::Colorblind
+            } else {
+                Style::Ansi
+            }
+        } else {
+            Style::Markers
+        }
+    }
+}
+
+pub fn feedback(word: &str, roles: &[Role], style: Style) -> String {
+    let mut text: String = String::new();
+    for (c, role) in word.chars().zip(roles.iter()) {
+        match style {
+            // Same colors as the ncurses letter boxes
+            Style::Ansi | Style::Colorblind => text.push_str(&format!(
+                "{} {} \x1b[0m",
+                match (role, style) {
+                    // Colors of the 256 color palette nearest to the ones of
+                    // the ncurses colorblind mode
+                    (Role::InPlace, Style::Colorblind) => "\x1b[97;48;5;209m",
+                    (Role::NotInPlace, Style::Colorblind) => "\x1b[97;48;5;68m",
+                    (Role::InPlace, _) => "\x1b[30;42m",
+                    (Role::NotInPlace, _) => "\x1b[30;43m",
+                    (Role::NotInWord, _) => "\x1b[37;40m",
+                    (Role::UnknownYet, _) => "\x1b[30;47m",
+                },
+                c.to_ascii_uppercase()
+            )),
+            Style::Markers => match role {
+                Role::InPlace => text.push_str(&format!("[{}]", c.to_ascii_uppercase())),
+                Role::NotInPlace => text.push_str(&format!("({})", c.to_ascii_lowercase())),
+                _ => text.push(c.to_ascii_lowercase()),
+            },
+        }
+    }
+    text
+}
+
+// Play until the game is over or the input ends
+pub fn play<R: BufRead, W: Write>(
+    input: R,
+    out: &mut W,
+    game: &mut Game,
+    words: Option<&[String]>,
+    style: Style,
+    game_id: &str,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    while game.state() == State::InProgress {
+        write!(out, "Guess {}/{}: ", game.guesses().len() + 1, game.tries())?;
+        out.flush()?;
+        let line: String = match lines.next() {
+            Some(line) => line?,
+            None => {
+                writeln!(out)?;
+                return Ok(());
+            }
+        };
+        let w: String = line.trim().to_uppercase();
+        if w.is_empty() {
+            continue;
+        }
+        match game.guess(&w, words) {
+            Ok(roles) => writeln!(out, "{}", feedback(&w, &roles, style))?,
+            Err(e) => writeln!(out, "{}: {}", w, e)?,
+        }
+    }
+    match game.state() {
+        State::Won(attempt) => writeln!(out, "You won from a {}'s attempt!", attempt)?,
+        _ => writeln!(out, "You lost! The word is: {}", game.secret())?,
+    }
+    if !game_id.is_empty() {
+        writeln!(out, "{}", game_id)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordle_engine::Rules;
+
+    #[test]
+    fn markers() {
+        let roles = wordle_engine::score("SHEEP", "SHARD");
+        assert_eq!(feedback("SHEEP", &roles, Style::Markers), "[S][H]eep");
+        let roles = wordle_engine::score("SHOE", "HOSE");
+        assert_eq!(feedback("shoe", &roles, Style::Markers), "(s)(h)(o)[E]");
+    }
+
+    #[test]
+    fn colorblind_colors() {
+        let roles = wordle_engine::score("AB", "AC");
+        assert_eq!(
+            feedback("AB", &roles, Style::Colorblind),
+            "\x1b[97;48;5;209m A \x1b[0m\x1b[37;40m B \x1b[0m"
+        );
+        assert_eq!(
+            feedback("AB", &roles, Style::Ansi),
+            "\x1b[30;42m A \x1b[0m\x1b[37;40m B \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn scripted_game() {
+        let words: Vec<String> = vec!["CRANE".to_string(), "SLATE".to_string()];
+        let mut game = Game::new("CRANE", 6, Rules::default());
+        let mut out: Vec<u8> = Vec::new();
+        let input: &[u8] = b"slate\nxxxxx\n\ncrane\nslate\n";
+        play(
+            input,
+            &mut out,
+            &mut game,
+            Some(&words),
+            Style::Markers,
+            "Seed 1",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Guess 1/6: sl[A]t[E]\nGuess 2/6: XXXXX: Not in a dictionary\n\
+             Guess 2/6: Guess 2/6: [C][R][A][N][E]\nYou won from a 2's attempt!\nSeed 1\n\n"
+        );
+        assert_eq!(game.state(), State::Won(2));
+    }
+
+    #[test]
+    fn input_end() {
+        let mut game = Game::new("CRANE", 6, Rules::default());
+        let mut out: Vec<u8> = Vec::new();
+        play(
+            &b"slate\n"[..],
+            &mut out,
+            &mut game,
+            None,
+            Style::Markers,
+            "",
+        )
+        .unwrap();
+        assert_eq!(game.state(), State::InProgress);
+        assert!(String::from_utf8(out).unwrap().ends_with("Guess 2/6: \n"));
+    }
+}
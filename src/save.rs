@@ -0,0 +1,183 @@
+/*
+ * Saved game
+ * ----------
+ * Unfinished game kept in $XDG_DATA_HOME/wordle-ncurses/saved as
+ * "<key> <value>" lines after a version header. Unknown keys are ignored
+ * so older versions of the program can read newer saves of the same version.
+ */
+
+use crate::error;
+use crate::paths;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const MAGIC: &str = "wordle-ncurses save";
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SavedGame {
+    pub wlen: i32,
+    pub tries: i32,
+    // One secret word per board
+    pub secrets: Vec<String>,
+    pub hard: bool,
+    // Adversarial game, the secret is one of the words still possible
+    pub absurd: bool,
+    pub puzzle: Option<i64>,
+    pub seed: Option<u64>,
+    // Checked words
+    pub guesses: Vec<String>,
+    // Letters typed in the current row
+    pub current: String,
+}
+
+impl SavedGame {
+    pub fn path() -> Option<PathBuf> {
+        paths::data_file("saved")
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        let version: u32 = lines.next()?.strip_prefix(MAGIC)?.trim().parse().ok()?;
+        if version != VERSION {
+            return None;
+        }
+        let mut g = SavedGame::default();
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "wlen" => g.wlen = value.parse().ok()?,
+                "tries" => g.tries = value.parse().ok()?,
+                "secret" => g.secrets.push(value.to_string()),
+                "hard" => g.hard = value == "1",
+                "absurd" => g.absurd = value == "1",
+                "puzzle" => g.puzzle = Some(value.parse().ok()?),
+                "seed" => g.seed = Some(value.parse().ok()?),
+                "guess" => g.guesses.push(value.to_string()),
+                "current" => g.current = value.to_string(),
+                _ => {}
+            }
+        }
+
+        // Don't offer something which can't be played
+        let wlen = g.wlen as usize;
+        if !(1..=error::MAX_WORD_LEN).contains(&g.wlen)
+            || !(1..=error::MAX_TRIES).contains(&g.tries)
+            || g.secrets.len() > error::MAX_BOARDS as usize
+            || g.secrets.is_empty()
+            || g.secrets.iter().any(|w| w.chars().count() != wlen)
+            || g.guesses.len() >= g.tries.max(0) as usize
+            || g.guesses.iter().any(|w| w.chars().count() != wlen)
+            || g.current.chars().count() > wlen
+        {
+            return None;
+        }
+        Some(g)
+    }
+
+    pub fn format(&self) -> String {
+        let mut text: String = format!("{} {}\n", MAGIC, VERSION);
+        text.push_str(&format!("wlen {}\ntries {}\n", self.wlen, self.tries));
+        for w in &self.secrets {
+            text.push_str(&format!("secret {}\n", w));
+        }
+        text.push_str(&format!("hard {}\n", if self.hard { 1 } else { 0 }));
+        text.push_str(&format!("absurd {}\n", if self.absurd { 1 } else { 0 }));
+        if let Some(n) = self.puzzle {
+            text.push_str(&format!("puzzle {}\n", n));
+        }
+        if let Some(n) = self.seed {
+            text.push_str(&format!("seed {}\n", n));
+        }
+        for w in &self.guesses {
+            text.push_str(&format!("guess {}\n", w));
+        }
+        text.push_str(&format!("current {}\n", self.current));
+        text
+    }
+
+    pub fn load() -> Option<Self> {
+        SavedGame::parse(&fs::read_to_string(SavedGame::path()?).ok()?)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = SavedGame::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.format())
+    }
+
+    pub fn remove() {
+        if let Some(path) = SavedGame::path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> SavedGame {
+        SavedGame {
+            wlen: 5,
+            tries: 6,
+            secrets: vec!["CRANE".to_string()],
+            hard: true,
+            absurd: false,
+            puzzle: None,
+            seed: Some(42),
+            guesses: vec!["SLATE".to_string(), "TRACE".to_string()],
+            current: "CR".to_string(),
+        }
+    }
+
+    #[test]
+    fn format_roundtrip() {
+        assert_eq!(SavedGame::parse(&game().format()), Some(game()));
+    }
+
+    #[test]
+    fn several_boards() {
+        let g = SavedGame {
+            secrets: vec!["CRANE".to_string(), "SLATE".to_string()],
+            ..game()
+        };
+        assert_eq!(SavedGame::parse(&g.format()), Some(g));
+    }
+
+    #[test]
+    fn absurd_game() {
+        let g = SavedGame {
+            absurd: true,
+            seed: None,
+            ..game()
+        };
+        assert_eq!(SavedGame::parse(&g.format()), Some(g));
+    }
+
+    #[test]
+    fn unknown_keys_ignored() {
+        let text = game().format() + "timer 12\n";
+        assert_eq!(SavedGame::parse(&text), Some(game()));
+    }
+
+    #[test]
+    fn rejects_other_versions_and_broken_saves() {
+        let text = game().format().replace("save 1", "save 2");
+        assert_eq!(SavedGame::parse(&text), None);
+        assert_eq!(SavedGame::parse("garbage"), None);
+        let text = game().format().replace("secret CRANE", "secret CRANES");
+        assert_eq!(SavedGame::parse(&text), None);
+        let text = game().format().replace("tries 6", "tries 31");
+        assert_eq!(SavedGame::parse(&text), None);
+        let g = SavedGame {
+            secrets: vec!["CRANE".to_string(); error::MAX_BOARDS as usize + 1],
+            ..game()
+        };
+        assert_eq!(SavedGame::parse(&g.format()), None);
+    }
+}
@@ -0,0 +1,208 @@
+/*
+ * Shareable result
+ * ----------------
+ * Spoiler free result grid, e.g.
+ *   Wordle-ncurses 3/6 #123
+ *   ⬛🟨⬛⬛🟩
+ *   🟩🟩⬛🟨🟩
+ *   🟩🟩🟩🟩🟩
+ */
+
+use wordle_engine::{Boards, Game, Role, State};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Result of a finished game
+pub fn game_text(game: &Game, puzzle: Option<i64>) -> String {
+    let rows: Vec<Vec<Role>> = game
+        .guesses()
+        .iter()
+        .map(|(_, roles)| roles.clone())
+        .collect();
+    let attempt: Option<usize> = match game.state() {
+        State::Won(n) => Some(n),
+        _ => None,
+    };
+    titled_text(
+        if game.is_adversarial() {
+            "Wordle-ncurses absurd"
+        } else {
+            "Wordle-ncurses"
+        },
+        &rows,
+        game.tries() as i32,
+        attempt,
+        puzzle,
+        game.rules().hard,
+    )
+}
+
+// Result of a finished multi-board game, one grid per board
+pub fn boards_text(games: &[Game], puzzle: Option<i64>) -> String {
+    if games.len() == 1 {
+        return game_text(&games[0], puzzle);
+    }
+    let boards = Boards::new(games.to_vec());
+    let attempt: Option<usize> = match boards.state() {
+        State::Won(n) => Some(n),
+        _ => None,
+    };
+    let mut text: String = header(
+        &format!("Wordle-ncurses {} boards", games.len()),
+        games[0].tries() as i32,
+        attempt,
+        puzzle,
+        games[0].rules().hard,
+    );
+    for game in games {
+        let rows: Vec<Vec<Role>> = game
+            .guesses()
+            .iter()
+            .map(|(_, roles)| roles.clone())
+            .collect();
+        text.push_str(&grid(&rows));
+    }
+    text
+}
+
+// `attempt` is the winning attempt number or None if the game is lost
+fn titled_text(
+    title: &str,
+    rows: &[Vec<Role>],
+    tries: i32,
+    attempt: Option<usize>,
+    puzzle: Option<i64>,
+    hard: bool,
+) -> String {
+    header(title, tries, attempt, puzzle, hard) + &grid(rows)
+}
+
+fn header(
+    title: &str,
+    tries: i32,
+    attempt: Option<usize>,
+    puzzle: Option<i64>,
+    hard: bool,
+) -> String {
+    let mut text: String = format!(
+        "{} {}/{}{}",
+        title,
+        match attempt {
+            Some(n) => n.to_string(),
+            None => "X".to_string(),
+        },
+        tries,
+        if hard { "*" } else { "" }
+    );
+    if let Some(n) = puzzle {
+        text.push_str(&format!(" #{}", n));
+    }
+    text.push('\n');
+    text
+}
+
+// Rows after an empty line
+fn grid(rows: &[Vec<Role>]) -> String {
+    let mut text: String = String::new();
+    for row in rows {
+        text.push('\n');
+        for role in row {
+            text.push(match role {
+                Role::InPlace => '🟩',
+                Role::NotInPlace => '🟨',
+                _ => '⬛',
+            });
+        }
+    }
+    text.push('\n');
+    text
+}
+
+fn base64(data: &[u8]) -> String {
+    let mut out: String = String::new();
+    for chunk in data.chunks(3) {
+        let b: [u32; 3] = [
+            chunk[0] as u32,
+            *chunk.get(1).unwrap_or(&0) as u32,
+            *chunk.get(2).unwrap_or(&0) as u32,
+        ];
+        let n: u32 = (b[0] << 16) | (b[1] << 8) | b[2];
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// OSC 52 terminal escape sequence putting the text to the clipboard
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordle_engine::Rules;
+
+    #[test]
+    fn won_daily() {
+        let mut game = Game::new("CRANE", 6, Rules::default());
+        game.guess("SCONE", None).unwrap();
+        game.guess("CRANE", None).unwrap();
+        assert_eq!(
+            game_text(&game, Some(123)),
+            "Wordle-ncurses 2/6 #123\n\n⬛🟨⬛🟩🟩\n🟩🟩🟩🟩🟩\n"
+        );
+    }
+
+    #[test]
+    fn lost_hard() {
+        let mut game = Game::new("AB", 2, Rules { hard: true });
+        game.guess("CC", None).unwrap();
+        game.guess("BA", None).unwrap();
+        assert_eq!(
+            game_text(&game, None),
+            "Wordle-ncurses X/2*\n\n⬛⬛\n🟨🟨\n"
+        );
+    }
+
+    #[test]
+    fn absurd_game() {
+        let words: Vec<String> = vec!["CAKES".to_string(), "MAKER".to_string()];
+        let mut game = Game::adversarial(words, 30, Default::default());
+        game.guess("CAKES", None).unwrap();
+        game.guess("MAKER", None).unwrap();
+        assert_eq!(
+            game_text(&game, None),
+            "Wordle-ncurses absurd 2/30\n\n⬛🟩🟩🟩⬛\n🟩🟩🟩🟩🟩\n"
+        );
+    }
+
+    #[test]
+    fn two_boards() {
+        let mut boards = Boards::new(vec![
+            Game::new("AB", 3, Default::default()),
+            Game::new("BA", 3, Default::default()),
+        ]);
+        boards.guess("AB", None).unwrap();
+        boards.guess("BA", None).unwrap();
+        assert_eq!(
+            boards_text(boards.games(), Some(7)),
+            "Wordle-ncurses 2 boards 2/3 #7\n\n🟩🟩\n\n🟨🟨\n🟩🟩\n"
+        );
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(osc52("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
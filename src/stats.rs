@@ -0,0 +1,424 @@
+/*
+ * Statistics
+ * ----------
+ * Game results kept in $XDG_DATA_HOME/wordle-ncurses/stats, one line per
+ * word length and amount of attempts:
+ *   <wlen> <tries> <played> <won> <streak> <max streak> <wins by attempt, comma separated>
+ * followed by the times of the timed wins if there are any, in a line of
+ * its own so version 1 of the program still reads the rest:
+ *   time <wlen> <tries> <timed wins> <total seconds> <best seconds>
+ * and one line per speedrun word length and amount of puzzles:
+ *   speedrun <wlen> <puzzles> <runs> <completed runs> <best total seconds>
+ * Files of a newer version are not overwritten.
+ */
+
+use crate::clock;
+use crate::error;
+use crate::help::{self, HelpElement};
+use crate::paths;
+use crate::utils;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const MAGIC: &str = "# wordle-ncurses stats";
+const VERSION: u32 = 2;
+const BAR_WIDTH: u32 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub wlen: i32,
+    pub tries: i32,
+    pub played: u32,
+    pub won: u32,
+    pub streak: u32,
+    pub max_streak: u32,
+    // Wins by the attempt number, index 0 is a win from the first attempt
+    pub hist: Vec<u32>,
+    // Wins with a known playing time, their total and best time in seconds
+    pub timed: u32,
+    pub total_secs: u64,
+    pub best_secs: u64,
+}
+
+impl Record {
+    fn new(wlen: i32, tries: i32) -> Self {
+        Record {
+            wlen,
+            tries,
+            played: 0,
+            won: 0,
+            streak: 0,
+            max_streak: 0,
+            hist: vec![0; tries as usize],
+            timed: 0,
+            total_secs: 0,
+            best_secs: 0,
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        if f.len() != 7 {
+            return None;
+        }
+        // Lines out of the supported ranges are skipped before the
+        // histogram of `tries` entries is allocated
+        let (wlen, tries): (i32, i32) = (f[0].parse().ok()?, f[1].parse().ok()?);
+        if !(1..=error::MAX_WORD_LEN).contains(&wlen) || !(1..=error::MAX_TRIES).contains(&tries) {
+            return None;
+        }
+        let mut r = Record::new(wlen, tries);
+        r.played = f[2].parse().ok()?;
+        r.won = f[3].parse().ok()?;
+        r.streak = f[4].parse().ok()?;
+        r.max_streak = f[5].parse().ok()?;
+        r.hist = f[6]
+            .split(',')
+            .map(|x| x.parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        r.hist.resize(r.tries as usize, 0);
+        Some(r)
+    }
+
+    // Apply a "time" line to the record it belongs to
+    fn parse_times(records: &mut [Record], line: &str) -> Option<()> {
+        let f: Vec<&str> = line.strip_prefix("time ")?.split_whitespace().collect();
+        if f.len() != 5 {
+            return None;
+        }
+        let (wlen, tries): (i32, i32) = (f[0].parse().ok()?, f[1].parse().ok()?);
+        let r: &mut Record = records
+            .iter_mut()
+            .find(|r| r.wlen == wlen && r.tries == tries)?;
+        r.timed = f[2].parse().ok()?;
+        r.total_secs = f[3].parse().ok()?;
+        r.best_secs = f[4].parse().ok()?;
+        Some(())
+    }
+
+    fn format(&self) -> String {
+        let hist: Vec<String> = self.hist.iter().map(|x| x.to_string()).collect();
+        let mut text: String = format!(
+            "{} {} {} {} {} {} {}",
+            self.wlen,
+            self.tries,
+            self.played,
+            self.won,
+            self.streak,
+            self.max_streak,
+            hist.join(",")
+        );
+        if self.timed > 0 {
+            text.push_str(&format!(
+                "\ntime {} {} {} {} {}",
+                self.wlen, self.tries, self.timed, self.total_secs, self.best_secs
+            ));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Speedrun {
+    pub wlen: i32,
+    pub puzzles: u32,
+    pub runs: u32,
+    // Runs with every puzzle solved, only they have a time
+    pub completed: u32,
+    pub best_secs: u64,
+}
+
+impl Speedrun {
+    fn parse(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.strip_prefix("speedrun ")?.split_whitespace().collect();
+        if f.len() != 5 {
+            return None;
+        }
+        Some(Speedrun {
+            wlen: f[0].parse().ok()?,
+            puzzles: f[1].parse().ok()?,
+            runs: f[2].parse().ok()?,
+            completed: f[3].parse().ok()?,
+            best_secs: f[4].parse().ok()?,
+        })
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "speedrun {} {} {} {} {}",
+            self.wlen, self.puzzles, self.runs, self.completed, self.best_secs
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    records: Vec<Record>,
+    speedruns: Vec<Speedrun>,
+    // Loaded from a file of a newer version, which is kept as it is
+    newer: bool,
+}
+
+impl Stats {
+    pub fn path() -> Option<PathBuf> {
+        paths::data_file("stats")
+    }
+
+    // Files without a header are of version 1
+    pub fn parse(text: &str) -> Self {
+        let version: u32 = text
+            .lines()
+            .next()
+            .and_then(|l| l.strip_prefix(MAGIC))
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(1);
+        let mut stats = Stats {
+            records: text
+                .lines()
+                .filter(|l| !l.starts_with('#'))
+                .filter_map(Record::parse)
+                .collect(),
+            speedruns: text.lines().filter_map(Speedrun::parse).collect(),
+            newer: version > VERSION,
+        };
+        for line in text.lines() {
+            Record::parse_times(&mut stats.records, line);
+        }
+        stats
+    }
+
+    // Missing or unreadable file gives empty statistics
+    pub fn load() -> Self {
+        match Stats::path().map(fs::read_to_string) {
+            Some(Ok(text)) => Stats::parse(&text),
+            _ => Stats::default(),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if self.newer {
+            return Err(io::Error::other(
+                "the file is of a newer version of the program",
+            ));
+        }
+        let path = Stats::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text: String = format!("{} {}\n", MAGIC, VERSION);
+        for r in &self.records {
+            text.push_str(&r.format());
+            text.push('\n');
+        }
+        for r in &self.speedruns {
+            text.push_str(&r.format());
+            text.push('\n');
+        }
+        fs::write(path, text)
+    }
+
+    pub fn get(&self, wlen: i32, tries: i32) -> Record {
+        self.records
+            .iter()
+            .find(|r| r.wlen == wlen && r.tries == tries)
+            .cloned()
+            .unwrap_or_else(|| Record::new(wlen, tries))
+    }
+
+    // Record a game result, `attempt` is the winning attempt number (from 1)
+    // or None if the game is lost, `time` is the playing time if known
+    pub fn add(&mut self, wlen: i32, tries: i32, attempt: Option<usize>, time: Option<Duration>) {
+        let idx = match self
+            .records
+            .iter()
+            .position(|r| r.wlen == wlen && r.tries == tries)
+        {
+            Some(idx) => idx,
+            None => {
+                self.records.push(Record::new(wlen, tries));
+                self.records.len() - 1
+            }
+        };
+        let r = &mut self.records[idx];
+        r.played += 1;
+        match attempt {
+            Some(n) if n >= 1 && n <= r.hist.len() => {
+                r.won += 1;
+                r.streak += 1;
+                r.max_streak = r.max_streak.max(r.streak);
+                r.hist[n - 1] += 1;
+                if let Some(t) = time {
+                    let secs: u64 = t.as_secs();
+                    r.best_secs = if r.timed == 0 {
+                        secs
+                    } else {
+                        r.best_secs.min(secs)
+                    };
+                    r.timed += 1;
+                    r.total_secs += secs;
+                }
+            }
+            _ => r.streak = 0,
+        }
+    }
+
+    pub fn get_speedrun(&self, wlen: i32, puzzles: u32) -> Option<&Speedrun> {
+        self.speedruns
+            .iter()
+            .find(|r| r.wlen == wlen && r.puzzles == puzzles)
+    }
+
+    // Record a speedrun, `total` is its time if every puzzle was solved
+    pub fn add_speedrun(&mut self, wlen: i32, puzzles: u32, total: Option<Duration>) {
+        let idx = match self
+            .speedruns
+            .iter()
+            .position(|r| r.wlen == wlen && r.puzzles == puzzles)
+        {
+            Some(idx) => idx,
+            None => {
+                self.speedruns.push(Speedrun {
+                    wlen,
+                    puzzles,
+                    runs: 0,
+                    completed: 0,
+                    best_secs: 0,
+                });
+                self.speedruns.len() - 1
+            }
+        };
+        let r = &mut self.speedruns[idx];
+        r.runs += 1;
+        if let Some(t) = total {
+            let secs: u64 = t.as_secs();
+            r.best_secs = if r.completed == 0 {
+                secs
+            } else {
+                r.best_secs.min(secs)
+            };
+            r.completed += 1;
+        }
+    }
+}
+
+// Display statistics for the word length and amount of attempts,
+// `last` is the winning attempt of the just finished game to highlight
+pub fn show(stats: &Stats, wlen: i32, tries: i32, last: Option<usize>) {
+    let r = stats.get(wlen, tries);
+    let win_pct: u32 = (r.won * 100).checked_div(r.played).unwrap_or(0);
+    let summary: String = format!(
+        "Played: {}   Win %: {}   Current streak: {}   Max streak: {}",
+        r.played, win_pct, r.streak, r.max_streak
+    );
+    let times: String = if r.timed > 0 {
+        format!(
+            "Best time: {}   Average time: {}",
+            clock::format(Duration::from_secs(r.best_secs)),
+            clock::format(Duration::from_secs(r.total_secs / r.timed as u64))
+        )
+    } else {
+        String::new()
+    };
+    let config: String = format!("{} letters, {} attempts", wlen, tries);
+    let max: u32 = r.hist.iter().copied().max().unwrap_or(0).max(1);
+    let labels: Vec<String> = (1..=r.hist.len()).map(|n| format!("{:>3} ", n)).collect();
+    let bars: Vec<String> = r
+        .hist
+        .iter()
+        .map(|&n| format!("{:>1$}", n, 2 + (n * BAR_WIDTH / max) as usize))
+        .collect();
+
+    let mut elements: Vec<HelpElement> = vec![
+        HelpElement::Color(utils::HELP_COLOR),
+        HelpElement::Text(&config),
+        HelpElement::NewLine,
+        HelpElement::NewLine,
+        HelpElement::Color(utils::NORM_COLOR),
+        HelpElement::Text(&summary),
+        HelpElement::NewLine,
+    ];
+    if r.timed > 0 {
+        elements.extend([HelpElement::Text(&times), HelpElement::NewLine]);
+    }
+    elements.extend([
+        HelpElement::NewLine,
+        HelpElement::Text("Guess distribution:"),
+        HelpElement::NewLine,
+    ]);
+    for (idx, (label, bar)) in labels.iter().zip(bars.iter()).enumerate() {
+        elements.push(HelpElement::Color(utils::NORM_COLOR));
+        elements.push(HelpElement::Text(label));
+        elements.push(HelpElement::Color(if last == Some(idx + 1) {
+            utils::IN_PLACE_COLOR
+        } else {
+            utils::BAR_COLOR
+        }));
+        elements.push(HelpElement::Text(bar));
+        elements.push(HelpElement::NewLine);
+    }
+    elements.push(HelpElement::NewLine);
+    elements.push(HelpElement::Color(utils::HELP_COLOR));
+    elements.push(HelpElement::Text("        Press any key to continue"));
+    elements.push(HelpElement::NewLine);
+    help::show_elements(" Statistics ", &elements);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaks_and_histogram() {
+        let mut s = Stats::default();
+        s.add(5, 6, Some(3), None);
+        s.add(5, 6, Some(4), None);
+        s.add(5, 6, None, None);
+        s.add(5, 6, Some(3), None);
+        s.add(6, 6, Some(1), None);
+        let r = s.get(5, 6);
+        assert_eq!((r.played, r.won, r.streak, r.max_streak), (4, 3, 1, 2));
+        assert_eq!(r.hist, vec![0, 0, 2, 1, 0, 0]);
+        assert_eq!(s.get(6, 6).won, 1);
+        assert_eq!(s.get(7, 6).played, 0);
+    }
+
+    #[test]
+    fn format_roundtrip() {
+        let mut s = Stats::default();
+        s.add(5, 6, Some(2), None);
+        s.add(4, 3, None, None);
+        let text: String = s.records.iter().map(|r| r.format() + "\n").collect();
+        let loaded = Stats::parse(&format!("{} {}\n{}garbage line\n", MAGIC, VERSION, text));
+        assert_eq!(loaded.records, s.records);
+        assert!(!loaded.newer);
+        // Older files have no times
+        assert_eq!(Stats::parse("5 6 1 1 1 1 0,1,0,0,0,0\n").get(5, 6).won, 1);
+        assert!(Stats::parse("# wordle-ncurses stats 3\n").newer);
+        // Attempts out of range don't make a histogram
+        let text: &str = "5 -1 1 1 1 1 0\n5 2000000000 1 1 1 1 0\n0 6 1 1 1 1 0\n";
+        assert!(Stats::parse(text).records.is_empty());
+    }
+
+    #[test]
+    fn times() {
+        let mut s = Stats::default();
+        s.add(5, 6, Some(3), Some(Duration::from_secs(90)));
+        s.add(5, 6, Some(2), Some(Duration::from_secs(30)));
+        s.add(5, 6, None, Some(Duration::from_secs(10)));
+        s.add(5, 6, Some(2), None);
+        let r = s.get(5, 6);
+        assert_eq!((r.timed, r.total_secs, r.best_secs), (2, 120, 30));
+        // Version 1 reads the first line, which has the usual 7 fields
+        assert_eq!(r.format(), "5 6 4 3 1 2 0,2,1,0,0,0\ntime 5 6 2 120 30");
+        assert_eq!(Stats::parse(&r.format()).get(5, 6), r);
+        s.add_speedrun(5, 3, None);
+        s.add_speedrun(5, 3, Some(Duration::from_secs(200)));
+        s.add_speedrun(5, 3, Some(Duration::from_secs(250)));
+        let text: String = s.speedruns.iter().map(|r| r.format() + "\n").collect();
+        assert_eq!(text, "speedrun 5 3 3 2 200\n");
+        assert_eq!(Stats::parse(&text).get_speedrun(5, 3), s.get_speedrun(5, 3));
+    }
+}
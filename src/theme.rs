@@ -0,0 +1,477 @@
+/*
+ * Color themes
+ * ------------
+ * Colors of the color pairs, reduced to what the terminal is able to show:
+ * own RGB colors if it can change colors, the nearest ones of the 256 color
+ * palette, the 8 basic colors, or text attributes on monochrome terminals.
+ */
+
+extern crate ncurses;
+
+use crate::utils;
+use ncurses::*;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ColorValue")]
+pub enum Color {
+    // Color of the terminal itself
+    Default,
+    // One of the 8 basic curses colors
+    Basic(i16),
+    // Entry of the 256 color palette
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+// Color in the config file, a name, "default", "#rrggbb" or a palette number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorValue {
+    Number(i64),
+    Text(String),
+}
+
+const COLOR_NAMES: [(&str, i16); 8] = [
+    ("black", COLOR_BLACK),
+    ("red", COLOR_RED),
+    ("green", COLOR_GREEN),
+    ("yellow", COLOR_YELLOW),
+    ("blue", COLOR_BLUE),
+    ("magenta", COLOR_MAGENTA),
+    ("cyan", COLOR_CYAN),
+    ("white", COLOR_WHITE),
+];
+
+// Basic colors as xterm shows them
+const BASIC_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl TryFrom<ColorValue> for Color {
+    type Error = String;
+
+    fn try_from(value: ColorValue) -> Result<Self, String> {
+        let text: String = match value {
+            ColorValue::Number(n) => {
+                return u8::try_from(n)
+                    .map(Color::Indexed)
+                    .map_err(|_| format!("color {} is out of range, expected 0 to 255", n))
+            }
+            ColorValue::Text(text) => text,
+        };
+        if text.eq_ignore_ascii_case("default") {
+            return Ok(Color::Default);
+        }
+        if let Some(hex) = text.strip_prefix('#') {
+            return match u32::from_str_radix(hex, 16) {
+                Ok(c) if hex.len() == 6 => Ok(rgb(c)),
+                _ => Err(format!("bad color \"{}\", expected #rrggbb", text)),
+            };
+        }
+        COLOR_NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(&text))
+            .map(|&(_, c)| Color::Basic(c))
+            .ok_or_else(|| {
+                let names: Vec<&str> = COLOR_NAMES.iter().map(|(n, _)| *n).collect();
+                format!(
+                    "unknown color \"{}\", expected one of {}, default, #rrggbb or 0 to 255",
+                    text,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Color {
+    // The terminal color is taken for black
+    fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Default => BASIC_RGB[0],
+            Color::Basic(n) => BASIC_RGB[n as usize & 7],
+            Color::Indexed(n) => indexed_rgb(n),
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+}
+
+// Colors of the xterm 256 color palette
+fn indexed_rgb(n: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match n {
+        0..=15 => BASIC_RGB[n as usize],
+        16..=231 => {
+            let c: usize = n as usize - 16;
+            (LEVELS[c / 36], LEVELS[c / 6 % 6], LEVELS[c % 6])
+        }
+        _ => {
+            let gray: u8 = 8 + 10 * (n - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+// Basic color with the channels brighter than a half, like the color
+// numbers are composed of red, green and blue bits
+fn nearest_basic((r, g, b): (u8, u8, u8)) -> i16 {
+    (r >= 128) as i16 | ((g >= 128) as i16) << 1 | ((b >= 128) as i16) << 2
+}
+
+// Nearest color of the palette, the basic colors are left out as
+// terminals show them differently
+fn nearest_indexed((r, g, b): (u8, u8, u8)) -> i16 {
+    let distance = |n: u8| {
+        let (pr, pg, pb) = indexed_rgb(n);
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    (16..=255).min_by_key(|&n| distance(n)).unwrap_or(0) as i16
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Color,
+    pub bg: Color,
+    pub bold: bool,
+}
+
+// Styles of the color pairs, indexed by the pair number. Pair 0 is the
+// screen background.
+pub type Theme = [Style; 16];
+
+const fn rgb(c: u32) -> Color {
+    Color::Rgb((c >> 16) as u8, (c >> 8) as u8, c as u8)
+}
+
+const fn basic(fg: i16, bg: i16) -> Style {
+    Style {
+        fg: Color::Basic(fg),
+        bg: Color::Basic(bg),
+        bold: false,
+    }
+}
+
+const fn rich(fg: u32, bg: u32) -> Style {
+    Style {
+        fg: rgb(fg),
+        bg: rgb(bg),
+        bold: false,
+    }
+}
+
+const fn bold(style: Style) -> Style {
+    Style {
+        bold: true,
+        ..style
+    }
+}
+
+pub const CLASSIC: Theme = [
+    Style {
+        fg: Color::Default,
+        bg: Color::Default,
+        bold: false,
+    },
+    basic(COLOR_RED, COLOR_BLACK),     // FOCUS_COLOR
+    basic(COLOR_CYAN, COLOR_BLACK),    // NO_FOCUS_COLOR
+    basic(COLOR_MAGENTA, COLOR_BLACK), // TITLE_COLOR
+    basic(COLOR_WHITE, COLOR_BLACK),   // UNKNOWN_COLOR
+    basic(COLOR_BLACK, COLOR_GREEN),   // IN_PLACE_COLOR
+    basic(COLOR_BLACK, COLOR_YELLOW),  // NOT_IN_PLACE_COLOR
+    basic(COLOR_WHITE, COLOR_BLACK),   // NOT_IN_WORD_COLOR
+    basic(COLOR_WHITE, COLOR_BLACK),   // NORM_COLOR
+    basic(COLOR_WHITE, COLOR_RED),     // ERR_COLOR
+    basic(COLOR_MAGENTA, COLOR_BLACK), // HELP_COLOR
+    basic(COLOR_BLACK, COLOR_CYAN),    // YESNO_SEL_COLOR
+    basic(COLOR_WHITE, COLOR_BLACK),   // YESNO_NSEL_COLOR
+    basic(COLOR_BLACK, COLOR_YELLOW),  // DEBUG_COLOR
+    basic(COLOR_BLACK, COLOR_WHITE),   // KEY_UNKNOWN_COLOR
+    basic(COLOR_BLACK, COLOR_WHITE),   // BAR_COLOR
+];
+
+// Colors of the web game in the dark mode
+const DARK: Theme = [
+    rich(0xd7dadc, 0x121213),       // Screen
+    bold(rich(0xd7dadc, 0x121213)), // FOCUS_COLOR
+    rich(0x3a3a3c, 0x121213),       // NO_FOCUS_COLOR
+    rich(0xc9b458, 0x121213),       // TITLE_COLOR
+    rich(0xffffff, 0x121213),       // UNKNOWN_COLOR
+    bold(rich(0xffffff, 0x538d4e)), // IN_PLACE_COLOR
+    bold(rich(0xffffff, 0xb59f3b)), // NOT_IN_PLACE_COLOR
+    bold(rich(0xffffff, 0x3a3a3c)), // NOT_IN_WORD_COLOR
+    rich(0xd7dadc, 0x121213),       // NORM_COLOR
+    rich(0xffffff, 0xb33a3a),       // ERR_COLOR
+    rich(0x818384, 0x121213),       // HELP_COLOR
+    rich(0x121213, 0xd7dadc),       // YESNO_SEL_COLOR
+    rich(0xd7dadc, 0x121213),       // YESNO_NSEL_COLOR
+    rich(0x121213, 0xc9b458),       // DEBUG_COLOR
+    rich(0xffffff, 0x818384),       // KEY_UNKNOWN_COLOR
+    rich(0xffffff, 0x3a3a3c),       // BAR_COLOR
+];
+
+// Colors of the web game in the light mode
+const LIGHT: Theme = [
+    rich(0x1a1a1b, 0xffffff),       // Screen
+    bold(rich(0x1a1a1b, 0xffffff)), // FOCUS_COLOR
+    rich(0xd3d6da, 0xffffff),       // NO_FOCUS_COLOR
+    rich(0x6aaa64, 0xffffff),       // TITLE_COLOR
+    rich(0x1a1a1b, 0xffffff),       // UNKNOWN_COLOR
+    bold(rich(0xffffff, 0x6aaa64)), // IN_PLACE_COLOR
+    bold(rich(0xffffff, 0xc9b458)), // NOT_IN_PLACE_COLOR
+    bold(rich(0xffffff, 0x787c7e)), // NOT_IN_WORD_COLOR
+    rich(0x1a1a1b, 0xffffff),       // NORM_COLOR
+    rich(0xffffff, 0xd7263d),       // ERR_COLOR
+    rich(0x787c7e, 0xffffff),       // HELP_COLOR
+    rich(0xffffff, 0x1a1a1b),       // YESNO_SEL_COLOR
+    rich(0x1a1a1b, 0xffffff),       // YESNO_NSEL_COLOR
+    rich(0x1a1a1b, 0xc9b458),       // DEBUG_COLOR
+    rich(0x1a1a1b, 0xd3d6da),       // KEY_UNKNOWN_COLOR
+    rich(0xffffff, 0x787c7e),       // BAR_COLOR
+];
+
+// Pure colors on black, bold letters
+const HIGH_CONTRAST: Theme = [
+    rich(0xffffff, 0x000000),       // Screen
+    bold(rich(0xffff00, 0x000000)), // FOCUS_COLOR
+    rich(0xffffff, 0x000000),       // NO_FOCUS_COLOR
+    bold(rich(0x00ffff, 0x000000)), // TITLE_COLOR
+    bold(rich(0xffffff, 0x000000)), // UNKNOWN_COLOR
+    bold(rich(0x000000, 0x00ff00)), // IN_PLACE_COLOR
+    bold(rich(0x000000, 0xffff00)), // NOT_IN_PLACE_COLOR
+    bold(rich(0xffffff, 0x585858)), // NOT_IN_WORD_COLOR
+    rich(0xffffff, 0x000000),       // NORM_COLOR
+    bold(rich(0xffffff, 0xff0000)), // ERR_COLOR
+    bold(rich(0x00ffff, 0x000000)), // HELP_COLOR
+    bold(rich(0x000000, 0xffffff)), // YESNO_SEL_COLOR
+    rich(0xffffff, 0x000000),       // YESNO_NSEL_COLOR
+    bold(rich(0x000000, 0xffff00)), // DEBUG_COLOR
+    bold(rich(0x000000, 0xffffff)), // KEY_UNKNOWN_COLOR
+    rich(0x000000, 0xffffff),       // BAR_COLOR
+];
+
+pub const BUILTIN: [(&str, Theme); 4] = [
+    ("classic", CLASSIC),
+    ("dark", DARK),
+    ("light", LIGHT),
+    ("high-contrast", HIGH_CONTRAST),
+];
+
+pub fn builtin(name: &str) -> Option<Theme> {
+    BUILTIN.iter().find(|(n, _)| *n == name).map(|(_, t)| *t)
+}
+
+// Orange and blue instead of green and yellow, which some people can't
+// tell apart. The blue stays blue on 8 color terminals.
+pub fn colorblind(theme: &mut Theme) {
+    for (pair, bg) in [
+        (utils::IN_PLACE_COLOR, 0xf5793a),
+        (utils::NOT_IN_PLACE_COLOR, 0x3b7ddd),
+    ] {
+        let style: &mut Style = &mut theme[pair as usize];
+        style.fg = rgb(0xffffff);
+        style.bg = rgb(bg);
+    }
+}
+
+// Attributes standing for the colors on monochrome terminals
+fn mono_attrs(pair: i16) -> attr_t {
+    match pair {
+        utils::FOCUS_COLOR | utils::TITLE_COLOR | utils::KEY_UNKNOWN_COLOR => A_BOLD(),
+        utils::IN_PLACE_COLOR => A_REVERSE() | A_BOLD(),
+        utils::NOT_IN_PLACE_COLOR => A_UNDERLINE() | A_BOLD(),
+        utils::NOT_IN_WORD_COLOR => A_DIM(),
+        utils::ERR_COLOR | utils::YESNO_SEL_COLOR | utils::DEBUG_COLOR | utils::BAR_COLOR => {
+            A_REVERSE()
+        }
+        _ => A_NORMAL(),
+    }
+}
+
+// What the terminal is able to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth {
+    Mono,
+    Basic,
+    Palette,
+    Rgb,
+}
+
+pub fn depth() -> Depth {
+    if !has_colors() {
+        Depth::Mono
+    } else if COLORS() < 256 {
+        Depth::Basic
+    } else if can_change_color() {
+        Depth::Rgb
+    } else {
+        Depth::Palette
+    }
+}
+
+// Color number shown at the depth. In the RGB depth the colors which are
+// not basic are defined from the number 16 on, `defined` lists them.
+fn reduce(color: Color, depth: Depth, defined: &mut Vec<(u8, u8, u8)>) -> i16 {
+    match (color, depth) {
+        (Color::Default, _) => -1,
+        (Color::Basic(n), _) => n,
+        (Color::Indexed(n), Depth::Palette) => n as i16,
+        (Color::Indexed(n), Depth::Rgb) if n < 16 => n as i16,
+        (_, Depth::Rgb) => {
+            let c: (u8, u8, u8) = color.to_rgb();
+            let idx: usize = defined.iter().position(|&d| d == c).unwrap_or_else(|| {
+                defined.push(c);
+                defined.len() - 1
+            });
+            16 + idx as i16
+        }
+        (_, Depth::Palette) => nearest_indexed(color.to_rgb()),
+        _ => nearest_basic(color.to_rgb()),
+    }
+}
+
+// Foreground and background numbers of a style at the depth, a
+// foreground which became the same as the background is replaced
+fn reduce_style(style: &Style, depth: Depth, defined: &mut Vec<(u8, u8, u8)>) -> (i16, i16) {
+    let fg: i16 = reduce(style.fg, depth, defined);
+    let bg: i16 = reduce(style.bg, depth, defined);
+    if fg != bg || style.fg == style.bg {
+        (fg, bg)
+    } else if bg == COLOR_BLACK {
+        (COLOR_WHITE, bg)
+    } else {
+        (COLOR_BLACK, bg)
+    }
+}
+
+// Initialize the color pairs with the theme, returns the attributes to be
+// added to every pair
+pub fn apply(theme: &Theme) -> [attr_t; 16] {
+    let depth: Depth = depth();
+    let mut attrs: [attr_t; 16] = [A_NORMAL(); 16];
+    let mut defined: Vec<(u8, u8, u8)> = Vec::new();
+    for (pair, style) in theme.iter().enumerate() {
+        let pair: i16 = pair as i16;
+        // Only the attributes tell the pairs apart without colors
+        if depth == Depth::Mono {
+            attrs[pair as usize] = mono_attrs(pair);
+            continue;
+        }
+        if style.bold {
+            attrs[pair as usize] = A_BOLD();
+        }
+        let (fg, bg) = reduce_style(style, depth, &mut defined);
+        if pair == 0 {
+            assume_default_colors(fg as i32, bg as i32);
+        } else {
+            init_pair(pair, fg, bg);
+        }
+    }
+    // Curses takes the channels from 0 to 1000
+    let scale = |c: u8| (c as i32 * 1000 / 255) as i16;
+    for (n, &(r, g, b)) in defined.iter().enumerate() {
+        init_color(16 + n as i16, scale(r), scale(g), scale(b));
+    }
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colors() {
+        let parse = |v: ColorValue| Color::try_from(v);
+        assert_eq!(
+            parse(ColorValue::Text("Blue".to_string())),
+            Ok(Color::Basic(COLOR_BLUE))
+        );
+        assert_eq!(
+            parse(ColorValue::Text("#538d4e".to_string())),
+            Ok(Color::Rgb(0x53, 0x8d, 0x4e))
+        );
+        assert_eq!(parse(ColorValue::Number(208)), Ok(Color::Indexed(208)));
+        assert!(parse(ColorValue::Number(256)).is_err());
+        assert!(parse(ColorValue::Text("#53d4e".to_string())).is_err());
+        assert!(parse(ColorValue::Text("gren".to_string())).is_err());
+    }
+
+    #[test]
+    fn reduces_colors() {
+        let mut defined: Vec<(u8, u8, u8)> = Vec::new();
+        // Green and yellow of the web game stay apart on 8 color terminals
+        assert_eq!(
+            reduce(rgb(0x6aaa64), Depth::Basic, &mut defined),
+            COLOR_GREEN
+        );
+        assert_eq!(
+            reduce(rgb(0xc9b458), Depth::Basic, &mut defined),
+            COLOR_YELLOW
+        );
+        assert_eq!(
+            reduce(Color::Indexed(196), Depth::Basic, &mut defined),
+            COLOR_RED
+        );
+        assert_eq!(reduce(rgb(0x121213), Depth::Palette, &mut defined), 233);
+        assert_eq!(reduce(rgb(0xff8700), Depth::Palette, &mut defined), 208);
+        assert_eq!(
+            reduce(Color::Indexed(208), Depth::Palette, &mut defined),
+            208
+        );
+        assert!(defined.is_empty());
+        assert_eq!(reduce(rgb(0x538d4e), Depth::Rgb, &mut defined), 16);
+        assert_eq!(
+            reduce(Color::Basic(COLOR_RED), Depth::Rgb, &mut defined),
+            COLOR_RED
+        );
+        assert_eq!(reduce(Color::Indexed(208), Depth::Rgb, &mut defined), 17);
+        assert_eq!(reduce(rgb(0x538d4e), Depth::Rgb, &mut defined), 16);
+        assert_eq!(defined, vec![(0x53, 0x8d, 0x4e), (0xff, 0x87, 0x00)]);
+    }
+
+    #[test]
+    fn keeps_text_visible() {
+        let mut defined: Vec<(u8, u8, u8)> = Vec::new();
+        // Both gray on 8 colors
+        let style: Style = rich(0x787c7e, 0x121213);
+        assert_eq!(
+            reduce_style(&style, Depth::Basic, &mut defined),
+            (COLOR_WHITE, COLOR_BLACK)
+        );
+        let style: Style = rich(0xffffff, 0xd3d6da);
+        assert_eq!(
+            reduce_style(&style, Depth::Basic, &mut defined),
+            (COLOR_BLACK, COLOR_WHITE)
+        );
+        assert_eq!(builtin("dark").map(|t| t[5].bold), Some(true));
+        assert_eq!(builtin("sepia"), None);
+        let mut theme: Theme = CLASSIC;
+        colorblind(&mut theme);
+        let in_place: Style = theme[utils::IN_PLACE_COLOR as usize];
+        let not_in_place: Style = theme[utils::NOT_IN_PLACE_COLOR as usize];
+        assert_eq!(
+            reduce_style(&in_place, Depth::Basic, &mut defined),
+            (COLOR_WHITE, COLOR_RED)
+        );
+        assert_eq!(
+            reduce_style(&not_in_place, Depth::Basic, &mut defined),
+            (COLOR_WHITE, COLOR_BLUE)
+        );
+    }
+}
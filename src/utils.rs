@@ -4,8 +4,12 @@
  */
 extern crate ncurses;
 
+use crate::theme::{self, Theme};
 use ncurses::*;
 use std::cmp::max;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 // Geometry
 pub const LEFT_BW: i32 = 1;
@@ -27,12 +31,43 @@ pub const HELP_COLOR: i16 = 10;
 pub const YESNO_SEL_COLOR: i16 = 11;
 pub const YESNO_NSEL_COLOR: i16 = 12;
 pub const DEBUG_COLOR: i16 = 13;
+pub const KEY_UNKNOWN_COLOR: i16 = 14;
+pub const BAR_COLOR: i16 = 15;
 
 /*
  * Global init
  * -----------
  */
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static RESIZED: AtomicBool = AtomicBool::new(false);
+static THEME: Mutex<Theme> = Mutex::new(theme::CLASSIC);
+// Attributes added to the color pairs, e.g. instead of colors on
+// monochrome terminals
+static ATTRS: Mutex<[attr_t; 16]> = Mutex::new([0; 16]);
+
+// Colors used by the next init, e.g. from the config file
+pub fn set_theme(theme: Theme) {
+    *THEME.lock().unwrap() = theme;
+}
+
+// Attributes to draw with a color pair
+pub fn color(pair: i16) -> attr_t {
+    COLOR_PAIR(pair) | ATTRS.lock().unwrap()[pair as usize]
+}
+
+// May be called more than once, the terminal is initialized once
 pub fn init() {
+    if ACTIVE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // Restore the terminal before a panic message is printed
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        end();
+        default_hook(info);
+    }));
+
     initscr();
     cbreak();
     noecho();
@@ -40,24 +75,50 @@ pub fn init() {
     keypad(stdscr(), true);
     start_color();
 
-    init_pair(FOCUS_COLOR, COLOR_RED, COLOR_BLACK);
-    init_pair(NO_FOCUS_COLOR, COLOR_CYAN, COLOR_BLACK);
-    init_pair(TITLE_COLOR, COLOR_MAGENTA, COLOR_BLACK);
-    init_pair(UNKNOWN_COLOR, COLOR_WHITE, COLOR_BLACK);
-    init_pair(IN_PLACE_COLOR, COLOR_BLACK, COLOR_GREEN);
-    init_pair(NOT_IN_PLACE_COLOR, COLOR_BLACK, COLOR_YELLOW);
-    init_pair(NOT_IN_WORD_COLOR, COLOR_WHITE, COLOR_BLACK);
-    init_pair(NORM_COLOR, COLOR_WHITE, COLOR_BLACK);
-    init_pair(ERR_COLOR, COLOR_WHITE, COLOR_RED);
-    init_pair(HELP_COLOR, COLOR_MAGENTA, COLOR_BLACK);
-    init_pair(YESNO_SEL_COLOR, COLOR_BLACK, COLOR_CYAN);
-    init_pair(YESNO_NSEL_COLOR, COLOR_WHITE, COLOR_BLACK);
-    init_pair(DEBUG_COLOR, COLOR_BLACK, COLOR_YELLOW);
+    *ATTRS.lock().unwrap() = theme::apply(&THEME.lock().unwrap());
 
     refresh();
 }
+// May be called more than once and before init, the terminal is restored once
 pub fn end() {
-    endwin();
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        endwin();
+    }
+}
+
+/*
+ * Terminal resize
+ * ---------------
+ * Dialogs follow the terminal size themselves and leave a note for the
+ * main loop to re-layout the windows below them
+ */
+pub fn set_resized() {
+    RESIZED.store(true, Ordering::SeqCst);
+}
+pub fn take_resized() -> bool {
+    RESIZED.swap(false, Ordering::SeqCst)
+}
+
+// Overlay displayed instead of the game when the terminal is too small,
+// `exit` is the name of the key quitting the game
+pub fn too_small(width: i32, height: i32, exit: &str) {
+    let msg: [String; 3] = [
+        "Terminal is too small".to_string(),
+        format!("at least {}x{} is needed", width, height),
+        format!("{} - Exit", exit),
+    ];
+    erase();
+    attrset(color(ERR_COLOR));
+    for (n, m) in msg.iter().enumerate() {
+        let m: String = m.chars().take(max(COLS() - 1, 0) as usize).collect();
+        mvprintw(
+            (LINES() - msg.len() as i32) / 2 + n as i32,
+            (COLS() - m.len() as i32) / 2,
+            &m,
+        );
+    }
+    attrset(color(NORM_COLOR));
+    refresh();
 }
 
 /*
@@ -93,15 +154,7 @@ impl Win {
             format!(" {} ", title_orig)
         };
         let w = newwin(height, width, y_offs, x_offs);
-        wattrset(
-            w,
-            COLOR_PAIR(if focus { FOCUS_COLOR } else { NO_FOCUS_COLOR }),
-        );
-        box_(w, 0, 0);
-        wattrset(w, COLOR_PAIR(TITLE_COLOR));
-        mvwprintw(w, 0, (width - title.len() as i32) / 2, title.as_str());
-        wrefresh(w);
-        Win {
+        let win = Win {
             title,
             w,
             height,
@@ -111,7 +164,38 @@ impl Win {
             focus,
             xcurs: LEFT_BW,
             ycurs: TOP_BW,
-        }
+        };
+        win.draw_frame();
+        win
+    }
+
+    fn draw_frame(&self) {
+        wattrset(
+            self.w,
+            color(if self.focus {
+                FOCUS_COLOR
+            } else {
+                NO_FOCUS_COLOR
+            }),
+        );
+        box_(self.w, 0, 0);
+        wattrset(self.w, color(TITLE_COLOR));
+        mvwprintw(
+            self.w,
+            0,
+            (self.width - self.title.len() as i32) / 2,
+            self.title.as_str(),
+        );
+        wrefresh(self.w);
+    }
+
+    // Recreate the window at a new position, the content should be redrawn
+    pub fn relocate(&mut self, x_offs: i32, y_offs: i32) {
+        delwin(self.w);
+        self.w = newwin(self.height, self.width, y_offs, x_offs);
+        self.x_offs = x_offs;
+        self.y_offs = y_offs;
+        self.draw_frame();
     }
 
     #[allow(dead_code)]
@@ -125,7 +209,7 @@ impl Win {
     pub fn box_(&self) {
         wattrset(
             self.w,
-            COLOR_PAIR(if self.focus {
+            color(if self.focus {
                 FOCUS_COLOR
             } else {
                 NO_FOCUS_COLOR
@@ -171,10 +255,10 @@ impl Win {
             wclrtoeol(self.w);
         }
         wmove(self.w, y, x);
-        wattrset(self.w, COLOR_PAIR(color));
+        wattrset(self.w, self::color(color));
         wprintw(self.w, msg);
         self.box_();
-        wattrset(self.w, COLOR_PAIR(TITLE_COLOR));
+        wattrset(self.w, self::color(TITLE_COLOR));
         mvwprintw(
             self.w,
             0,
@@ -200,7 +284,6 @@ pub struct Yesnowin {
     pub title: String,
     pub exit_msg: String,
     pub win: Win,
-    #[allow(dead_code)]
     yesno_height: i32,
     yesno_width: i32,
     #[allow(dead_code)]
@@ -211,16 +294,24 @@ pub struct Yesnowin {
 }
 
 impl Yesnowin {
+    fn position(title: &str, exit_msg: &str, yesno_height: i32) -> (i32, i32) {
+        (
+            (COLS() - LEFT_BW - RIGHT_BW - max(title.len() + 4, exit_msg.len() - 4) as i32) / 2
+                + LEFT_BW,
+            (LINES() - TOP_BW - BOT_BW - yesno_height) / 2 + TOP_BW,
+        )
+    }
+
     pub fn new(title: &str, exit_msg: &str) -> Self {
         let yesno_height = YESNO_HEIGHT;
+        let (x, y) = Yesnowin::position(title, exit_msg, yesno_height);
         let win = Win::new(
             title,
             false,
             yesno_height,
             max(title.len() + 4, exit_msg.len()) as i32 + 6,
-            (COLS() - LEFT_BW - RIGHT_BW - max(title.len() + 4, exit_msg.len() - 4) as i32) / 2
-                + LEFT_BW,
-            (LINES() - TOP_BW - BOT_BW - yesno_height) / 2 + TOP_BW,
+            x,
+            y,
         );
         Yesnowin {
             title: title.to_string(),
@@ -282,6 +373,17 @@ impl Yesnowin {
         loop {
             let ch = getch();
             match ch {
+                KEY_RESIZE => {
+                    set_resized();
+                    clear();
+                    refresh();
+                    let (x, y) = Yesnowin::position(&self.title, &self.exit_msg, self.yesno_height);
+                    self.win.relocate(x, y);
+                    replace_panel(p, self.win.w);
+                    self.win
+                        .print(false, 2, 1, NORM_COLOR, self.exit_msg.as_str());
+                    self.draw_yesno();
+                }
                 KEY_LEFT | KEY_RIGHT | 9 => {
                     self.yes = !self.yes;
                     self.draw_yesno();
@@ -314,6 +416,7 @@ pub fn yes_no(title: &str, exit_msg: &str) -> bool {
 /*
  * OK MESSAGEBOX
  * -------------
+ * Message with a row of buttons below it, e.g. just OK or a choice
  */
 pub struct Msgbox {
     pub title: String,
@@ -321,25 +424,37 @@ pub struct Msgbox {
     pub win: Win,
     msg_height: i32,
     msg_width: i32,
-    ok_box: bool,
+    buttons: Vec<String>,
+    selected: usize,
 }
 
 impl Msgbox {
+    fn position(msg_width: i32, msg_height: i32) -> (i32, i32) {
+        (
+            (COLS() - LEFT_BW - RIGHT_BW - msg_width) / 2 + LEFT_BW,
+            (LINES() - TOP_BW - BOT_BW - msg_height) / 2 + TOP_BW,
+        )
+    }
+
     pub fn new(title: &str, msg_s: &str, ok_box: bool) -> Self {
+        Msgbox::with_buttons(title, msg_s, if ok_box { &["OK"] } else { &[] })
+    }
+
+    pub fn with_buttons(title: &str, msg_s: &str, buttons: &[&str]) -> Self {
         let msg: Vec<String> = msg_s.lines().map(|x| x.to_string()).collect();
+        let buttons: Vec<String> = buttons.iter().map(|b| format!("[ {} ]", b)).collect();
         let mut msg_height: i32 = msg.len() as i32 + 2 + TOP_BW + BOT_BW;
-        if ok_box {
+        if !buttons.is_empty() {
             msg_height += 2;
         }
-        let mut msg_width: i32 = title.len() as i32;
+        let mut msg_width: i32 = max(title.len() as i32, Msgbox::buttons_width(&buttons));
         for m in &msg {
             if msg_width < m.len() as i32 {
                 msg_width = m.len() as i32;
             }
         }
         msg_width += 2 + LEFT_BW + LEFT_BW;
-        let msg_x: i32 = (COLS() - LEFT_BW - RIGHT_BW - msg_width) / 2 + LEFT_BW;
-        let msg_y: i32 = (LINES() - TOP_BW - BOT_BW - msg_height) / 2 + TOP_BW;
+        let (msg_x, msg_y) = Msgbox::position(msg_width, msg_height);
         let win = Win::new(title, false, msg_height, msg_width, msg_x, msg_y);
         Msgbox {
             title: title.to_string(),
@@ -347,34 +462,90 @@ impl Msgbox {
             win,
             msg_height,
             msg_width,
-            ok_box,
+            buttons,
+            selected: 0,
         }
     }
 
-    pub fn run(&mut self) {
-        let p: PANEL = new_panel(self.win.w);
+    // Buttons are two spaces apart
+    fn buttons_width(buttons: &[String]) -> i32 {
+        buttons.iter().map(|b| b.len() as i32 + 2).sum::<i32>() - 2
+    }
+
+    fn draw(&self) {
         let mut y: i32 = 1;
         for m in &self.msg {
             self.win.print(false, 1, y, NORM_COLOR, m);
             y += 1;
         }
-        if self.ok_box {
+        let mut x: i32 = (self.win.width - Msgbox::buttons_width(&self.buttons)) / 2;
+        for (idx, b) in self.buttons.iter().enumerate() {
             self.win.print(
                 false,
-                (self.win.width - "[ OK ]".len() as i32) / 2,
+                x,
                 y + 1,
-                YESNO_SEL_COLOR,
-                "[ OK ]",
+                if idx == self.selected {
+                    YESNO_SEL_COLOR
+                } else {
+                    YESNO_NSEL_COLOR
+                },
+                b,
             );
+            x += b.len() as i32 + 2;
         }
+    }
+
+    // Returns the chosen button, with less than two buttons any key closes
+    // the box. The first letter of a button chooses it as well.
+    pub fn run(&mut self) -> usize {
+        let p: PANEL = new_panel(self.win.w);
+        self.draw();
         show_panel(p);
-        getch();
+        loop {
+            let ch = getch();
+            match ch {
+                KEY_RESIZE => {
+                    set_resized();
+                    clear();
+                    refresh();
+                    let (x, y) = Msgbox::position(self.msg_width, self.msg_height);
+                    self.win.relocate(x, y);
+                    replace_panel(p, self.win.w);
+                    self.draw();
+                }
+                _ if self.buttons.len() < 2 => break,
+                KEY_LEFT => {
+                    self.selected = (self.selected + self.buttons.len() - 1) % self.buttons.len();
+                    self.draw();
+                }
+                KEY_RIGHT | 9 => {
+                    self.selected = (self.selected + 1) % self.buttons.len();
+                    self.draw();
+                }
+                KEY_ENTER | 10 | 13 => break,
+                _ if ch < 256 => {
+                    let c: char = (ch as u8).to_ascii_uppercase() as char;
+                    if let Some(idx) = self.buttons.iter().position(|b| b[2..].starts_with(c)) {
+                        self.selected = idx;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
         hide_panel(p);
         update_panels();
         del_panel(p);
+        self.selected
     }
 }
 pub fn msg(title: &str, msg_s: &str, ok_box: bool) {
     let mut msg: Msgbox = Msgbox::new(title, msg_s, ok_box);
     msg.run();
 }
+
+// Message with a choice of buttons, returns the index of the chosen one
+pub fn choice(title: &str, msg_s: &str, buttons: &[&str]) -> usize {
+    let mut msg: Msgbox = Msgbox::with_buttons(title, msg_s, buttons);
+    msg.run()
+}